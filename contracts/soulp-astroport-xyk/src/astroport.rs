@@ -0,0 +1,313 @@
+//! Minimal subset of the Astroport pair interface used by this contract.
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Isqrt, QuerierWrapper, StdResult, Uint128, Uint256};
+use r#impl::execute::Token;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{ContractError, ContractResult};
+
+#[cw_serde]
+pub enum AssetInfo {
+  Token { contract_addr: Addr },
+  NativeToken { denom: String },
+}
+
+impl AssetInfo {
+  /// Denom of a native asset, if any
+  pub fn native_denom(&self) -> Option<&str> {
+    match self {
+      AssetInfo::NativeToken { denom } => Some(denom),
+      AssetInfo::Token { .. } => None,
+    }
+  }
+}
+
+#[cw_serde]
+pub struct Asset {
+  pub info: AssetInfo,
+  pub amount: Uint128,
+}
+
+#[cw_serde]
+pub enum PairType {
+  Xyk {},
+  Stable {},
+  Custom(String),
+}
+
+#[cw_serde]
+pub struct PairInfo {
+  pub asset_infos: Vec<AssetInfo>,
+  pub contract_addr: Addr,
+  /// LP token, which is a native denom on newer & a cw20 contract on older pools
+  pub liquidity_token: String,
+  pub pair_type: PairType,
+}
+
+#[cw_serde]
+pub struct PoolResponse {
+  pub assets: Vec<Asset>,
+  pub total_share: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulationResponse {
+  pub return_amount: Uint128,
+  pub spread_amount: Uint128,
+  pub commission_amount: Uint128,
+}
+
+/// Subset of the pair config. Not `cw_serde` as it must tolerate unknown fields.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+  pub factory_addr: Addr,
+}
+
+/// Subset of the factory fee info. Not `cw_serde` as it must tolerate unknown fields.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeInfoResponse {
+  pub total_fee_bps: u16,
+}
+
+#[cw_serde]
+pub enum PairQueryMsg {
+  Pair {},
+  /// Current reserves & total LP supply
+  Pool {},
+  /// Underlying assets of the given amount of LP tokens
+  Share { amount: Uint128 },
+  /// Simulate swapping the offer asset
+  Simulation {
+    offer_asset: Asset,
+    ask_asset_info: Option<AssetInfo>,
+  },
+  Config {},
+}
+
+#[cw_serde]
+pub enum FactoryQueryMsg {
+  FeeInfo { pair_type: PairType },
+}
+
+#[cw_serde]
+pub enum PairExecuteMsg {
+  ProvideLiquidity {
+    assets: Vec<Asset>,
+    slippage_tolerance: Option<Decimal>,
+    auto_stake: Option<bool>,
+    receiver: Option<String>,
+  },
+  Swap {
+    offer_asset: Asset,
+    ask_asset_info: Option<AssetInfo>,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    to: Option<String>,
+  },
+}
+
+/// Query the pair info of the given Astroport pair, ensuring it is an XYK pair.
+pub fn query_xyk_pair(querier: &QuerierWrapper, pair: &Addr) -> ContractResult<PairInfo> {
+  let info: PairInfo = querier.query_wasm_smart(pair, &PairQueryMsg::Pair {})?;
+  if info.contract_addr != pair {
+    return Err(ContractError::Generic(format!("Contract {} is not an Astroport pair", pair)));
+  }
+  if info.pair_type != (PairType::Xyk {}) {
+    return Err(ContractError::Generic(format!("Pair {} is not an XYK pair", pair)));
+  }
+  Ok(info)
+}
+
+/// LP token of the given pair. Older pairs issue a cw20 contract, newer a tokenfactory denom,
+/// which is told apart by whether the liquidity token is a contract.
+pub fn lp_token(querier: &QuerierWrapper, info: &PairInfo) -> Token {
+  match querier.query_wasm_contract_info(&info.liquidity_token) {
+    Ok(_) => Token::Cw20(info.liquidity_token.clone()),
+    Err(_) => Token::Native(info.liquidity_token.clone()),
+  }
+}
+
+/// Query the total swap fee of an XYK pair in basis points from its factory.
+pub fn query_total_fee_bps(querier: &QuerierWrapper, pair: &str) -> StdResult<u16> {
+  let config: ConfigResponse = querier.query_wasm_smart(pair, &PairQueryMsg::Config {})?;
+  let fee: FeeInfoResponse = querier.query_wasm_smart(config.factory_addr, &FactoryQueryMsg::FeeInfo {
+    pair_type: PairType::Xyk {},
+  })?;
+  Ok(fee.total_fee_bps)
+}
+
+/// Amount of a single asset to swap such that the remainder & the swap's return match the pool
+/// ratio after the swap, so both can be provided as liquidity without leftovers.
+///
+/// XYK pairs deduct their fee `f` from the return amount. Swapping `s` of `amount` against the
+/// `reserve` of the offer asset then requires `s² + s·(reserve·(2 - f) - amount·f) - amount·reserve = 0`.
+pub fn optimal_swap_amount(amount: Uint128, reserve: Uint128, fee_bps: u16) -> Uint128 {
+  let scale = Uint256::from(10_000u128);
+  let fee = Uint256::from(fee_bps as u128);
+  let amount = Uint256::from(amount);
+  let reserve = Uint256::from(reserve);
+
+  // Scaled by 10_000 to remain integral
+  let b_pos = reserve * (scale + scale - fee);
+  let b_neg = amount * fee;
+  let discriminant = (Uint256::from(4u128) * amount * reserve * scale * scale)
+    + if b_pos > b_neg { (b_pos - b_neg) * (b_pos - b_neg) } else { (b_neg - b_pos) * (b_neg - b_pos) };
+  let root = discriminant.isqrt();
+  let swap = if b_pos > b_neg {
+    (root - (b_pos - b_neg)) / (scale + scale)
+  } else {
+    (root + (b_neg - b_pos)) / (scale + scale)
+  };
+  Uint128::try_from(swap.min(amount)).unwrap_or_default()
+}
+
+#[cfg(test)]
+pub mod testing {
+  use super::*;
+  use cosmwasm_std::testing::MockQuerier;
+  use cosmwasm_std::{from_json, to_json_binary, Binary, ContractInfoResponse, ContractResult, SystemError, SystemResult, WasmQuery};
+
+  /// Mock Astroport pair of `uatom` & `untrn`.
+  pub struct MockPair {
+    pub info: PairInfo,
+    /// Pool reserves of `uatom` & `untrn`, respectively
+    pub reserves: [u128; 2],
+    /// Total supply of the LP token
+    pub total_share: u128,
+    /// Total swap fee, served by the mock factory at `factory`
+    pub fee_bps: u16,
+    /// Whether the LP token is a cw20 contract, i.e. served contract info
+    pub cw20_lp: bool,
+  }
+
+  impl MockPair {
+    pub fn new(pair: &str, liquidity_token: &str, pair_type: PairType) -> Self {
+      Self {
+        info: PairInfo {
+          asset_infos: vec![
+            AssetInfo::NativeToken { denom: "uatom".to_string() },
+            AssetInfo::NativeToken { denom: "untrn".to_string() },
+          ],
+          contract_addr: Addr::unchecked(pair),
+          liquidity_token: liquidity_token.to_string(),
+          pair_type,
+        },
+        reserves: [1_000_000, 4_000_000],
+        total_share: 2_000_000,
+        fee_bps: 30,
+        cw20_lp: false,
+      }
+    }
+
+    pub fn with_cw20_lp(mut self) -> Self {
+      self.cw20_lp = true;
+      self
+    }
+
+    pub fn with_pool(mut self, reserves: [u128; 2], total_share: u128) -> Self {
+      self.reserves = reserves;
+      self.total_share = total_share;
+      self
+    }
+
+    fn share(&self, amount: Uint128) -> Vec<Asset> {
+      self.info.asset_infos.iter().zip(self.reserves)
+        .map(|(info, reserve)| Asset {
+          info: info.clone(),
+          amount: Uint128::new(reserve).multiply_ratio(amount, self.total_share),
+        })
+        .collect()
+    }
+
+    fn query(&self, msg: PairQueryMsg) -> Binary {
+      match msg {
+        PairQueryMsg::Pair {} => to_json_binary(&self.info).unwrap(),
+        PairQueryMsg::Pool {} => to_json_binary(&PoolResponse {
+          assets: self.share(Uint128::new(self.total_share)),
+          total_share: Uint128::new(self.total_share),
+        }).unwrap(),
+        PairQueryMsg::Share { amount } => to_json_binary(&self.share(amount)).unwrap(),
+        PairQueryMsg::Simulation { offer_asset, .. } => to_json_binary(&self.simulate(offer_asset)).unwrap(),
+        PairQueryMsg::Config {} => to_json_binary(&ConfigResponse {
+          factory_addr: Addr::unchecked("factory"),
+        }).unwrap(),
+      }
+    }
+
+    /// Constant product swap with the fee deducted from the return amount
+    fn simulate(&self, offer_asset: Asset) -> SimulationResponse {
+      let index = self.info.asset_infos.iter().position(|info| info == &offer_asset.info).unwrap();
+      let offer_pool = Uint128::new(self.reserves[index]);
+      let ask_pool = Uint128::new(self.reserves[1 - index]);
+      let return_amount = ask_pool.multiply_ratio(offer_asset.amount, offer_pool + offer_asset.amount);
+      let commission_amount = return_amount.multiply_ratio(self.fee_bps, 10_000u128);
+      SimulationResponse {
+        return_amount: return_amount - commission_amount,
+        spread_amount: Uint128::zero(),
+        commission_amount,
+      }
+    }
+
+    pub fn mock(self, querier: &mut MockQuerier) {
+      querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == self.info.contract_addr.as_str() => {
+          match from_json(msg) {
+            Ok(msg) => SystemResult::Ok(ContractResult::Ok(self.query(msg))),
+            Err(err) => SystemResult::Ok(ContractResult::Err(err.to_string())),
+          }
+        }
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == "factory" => {
+          match from_json(msg) {
+            Ok(FactoryQueryMsg::FeeInfo { .. }) => SystemResult::Ok(ContractResult::Ok(to_json_binary(&FeeInfoResponse {
+              total_fee_bps: self.fee_bps,
+            }).unwrap())),
+            Err(err) => SystemResult::Ok(ContractResult::Err(err.to_string())),
+          }
+        }
+        WasmQuery::ContractInfo { contract_addr } if self.cw20_lp && contract_addr == &self.info.liquidity_token => {
+          let mut info = ContractInfoResponse::default();
+          info.code_id = 1;
+          info.creator = "creator".to_string();
+          SystemResult::Ok(ContractResult::Ok(to_json_binary(&info).unwrap()))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: format!("{:?}", query) }),
+      });
+    }
+  }
+
+  /// Mock an Astroport pair with the given LP token & type at `pair`.
+  pub fn mock_pair(querier: &mut MockQuerier, pair: &str, liquidity_token: &str, pair_type: PairType) {
+    MockPair::new(pair, liquidity_token, pair_type).mock(querier);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_optimal_swap_amount_without_fee() {
+    // sqrt(1000² + 1000 * 3000) - 1000 = 1000
+    assert_eq!(optimal_swap_amount(Uint128::new(3000), Uint128::new(1000), 0).u128(), 1000);
+  }
+
+  #[test]
+  fn test_optimal_swap_amount_balances_pool() {
+    let reserves = [1_000_000u128, 4_000_000];
+    let amount = Uint128::new(100_000);
+    let swap = optimal_swap_amount(amount, Uint128::new(reserves[0]), 30);
+
+    // Swap against the pool, deducting the fee from the return
+    let offer_pool = Uint128::new(reserves[0]);
+    let ask_pool = Uint128::new(reserves[1]);
+    let return_amount = ask_pool.multiply_ratio(swap, offer_pool + swap);
+    let return_amount = return_amount - return_amount.multiply_ratio(30u128, 10_000u128);
+
+    // The remainder matches the new pool ratio within rounding
+    let lhs = (amount - swap).full_mul(ask_pool - return_amount);
+    let rhs = return_amount.full_mul(offer_pool + swap);
+    let diff = if lhs > rhs { lhs - rhs } else { rhs - lhs };
+    assert!(diff < Uint256::from(ask_pool.u128() * 2), "remainder is not balanced: {} vs {}", lhs, rhs);
+  }
+}
@@ -1,19 +1,23 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, Uint128};
 use cw2::set_contract_version;
 
 use r#impl::ContractError;
-use r#impl::tokenfactory::{self, TFToken};
+use r#impl::execute::Token;
+use r#impl::migrate::{ensure_migratable, Version};
+use r#impl::tokenfactory::TFToken;
 
-use crate::msg::InstantiateMsg;
-use crate::state::{State, STATE};
+use crate::astroport::{lp_token, query_xyk_pair};
+use crate::msg::{InstantiateMsg, MigrateMsg};
+use crate::state::{State, Totals, STATE, STATE_V0_1, TOTALS};
 
 // version info for migration info
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-pub const SUBDENOM: &str = "SouLP";
+/// Subdenom of deployments prior to v0.2, which did not support configuring it
+pub const LEGACY_SUBDENOM: &str = "SouLP";
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -24,15 +28,28 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
   set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-  let token = tokenfactory::osmosis::TFToken::new(env.contract.address, SUBDENOM.to_string());
+  if msg.subdenom.is_empty() {
+    return Err(ContractError::Generic("Subdenom must not be empty".to_string()));
+  }
+
+  let pair = deps.api.addr_validate(&msg.pair)?;
+  let pair_info = query_xyk_pair(&deps.querier, &pair)?;
 
   let state = State {
-    pool: msg.pool,
+    pool: lp_token(&deps.querier, &pair_info),
+    pair: pair.to_string(),
+    asset_infos: pair_info.asset_infos,
     evacuate_address: info.sender.to_string(),
     mint_ratio: msg.mint_ratio,
+    subdenom: msg.subdenom,
+    owner: Some(info.sender.to_string()),
+    pending_owner: None,
   };
 
   STATE.save(deps.storage, &state)?;
+  TOTALS.save(deps.storage, &Totals::default())?;
+
+  let token = state.token(env.contract.address);
 
   Ok(Response::new()
     .add_attribute("method", "instantiate")
@@ -40,14 +57,94 @@ pub fn instantiate(
     // GET /osmosis/tokenfactory/v1beta1/params
     // Since the contract doesn't exist at this point yet, the fee must be sent in the `info.funds`.
     .add_messages(token.create())
+    .add_messages(token.set_metadata(msg.metadata))
+  )
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+  let from = ensure_migratable(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+  // Transforms are applied in order, each bringing the state up to the given version
+  if from < Version::new(0, 2, 0) {
+    migrate_v0_2(deps.branch(), &env, msg.pair)?;
+  }
+
+  set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+  Ok(Response::new()
+    .add_attribute("method", "migrate")
+    .add_attribute("from_version", from.to_string())
+    .add_attribute("to_version", CONTRACT_VERSION)
   )
 }
 
+/// v0.1 did not keep any accounting. As its `execute` dropped the mint messages, all LP held by
+/// the contract was locked without minting any SouLP. It also had no owner, so the evacuation
+/// address, which used to be the instantiator, assumes ownership. Neither did it record the pair,
+/// which must hence be provided & match the locked LP token.
+fn migrate_v0_2(deps: DepsMut, env: &Env, pair: Option<String>) -> Result<(), ContractError> {
+  let legacy = STATE_V0_1.load(deps.storage)?;
+
+  let pair = pair.ok_or_else(|| ContractError::Generic("Pair is required to migrate from v0.1".to_string()))?;
+  let pair = deps.api.addr_validate(&pair)?;
+  let pair_info = query_xyk_pair(&deps.querier, &pair)?;
+  if pair_info.liquidity_token != legacy.pool {
+    return Err(ContractError::Generic(format!("Pair {} does not issue {}", pair, legacy.pool)));
+  }
+
+  // v0.1 only supported native LP tokens
+  let state = State {
+    pool: Token::Native(legacy.pool),
+    pair: pair.to_string(),
+    asset_infos: pair_info.asset_infos,
+    owner: Some(legacy.evacuate_address.clone()),
+    pending_owner: None,
+    evacuate_address: legacy.evacuate_address,
+    mint_ratio: legacy.mint_ratio,
+    subdenom: LEGACY_SUBDENOM.to_string(),
+  };
+  STATE.save(deps.storage, &state)?;
+
+  let balance = state.pool.query_balance(&deps.querier, env.contract.address.clone())?;
+  TOTALS.save(deps.storage, &Totals {
+    locked: balance,
+    minted: Uint128::zero(),
+    burned: Uint128::zero(),
+    donated: Uint128::zero(),
+  })?;
+  set_contract_version(deps.storage, CONTRACT_NAME, "0.2.0")?;
+  Ok(())
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
   use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
   use cosmwasm_std::{coins, Decimal};
+  use cosmwasm_std::{CosmosMsg, SubMsg};
+  use cw2::get_contract_version;
+  use prost::Message;
+  use r#impl::tokenfactory::TokenMetadata;
+  use r#impl::tokenfactory::osmosis::MsgSetDenomMetadata;
+  use crate::astroport::PairType;
+  use crate::astroport::testing::{mock_pair, MockPair};
+  use crate::state::StateV0_1;
+
+  fn instantiate_msg(pair: &str) -> InstantiateMsg {
+    InstantiateMsg {
+      pair: pair.to_string(),
+      mint_ratio: Decimal::percent(100),
+      subdenom: "ATOMNTRN".to_string(),
+      metadata: TokenMetadata {
+        name: "ATOM-NTRN SouLP".to_string(),
+        symbol: "ATOMNTRN".to_string(),
+        description: "Permanently locked ATOM-NTRN liquidity".to_string(),
+        exponent: 6,
+        logo_uri: Some("https://example.com/logo.png".to_string()),
+      },
+    }
+  }
 
   #[test]
   fn test_instantiate() {
@@ -55,26 +152,161 @@ mod tests {
 
     let pool = "pool_token_address".to_string();
     let creator = "creator_address".to_string();
+    mock_pair(&mut deps.querier, "pair_address", &pool, PairType::Xyk {});
 
-    let msg = InstantiateMsg {
-      pool: pool.clone(),
-      mint_ratio: Decimal::percent(100),
-    };
+    let msg = instantiate_msg("pair_address");
     let info = mock_info(&creator, &coins(1000, "earth"));
 
     // Call instantiate
-    let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let env = mock_env();
+    let res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-    // Check response
-    assert_eq!(0, res.messages.len());
+    // Check response (MsgCreateDenom & MsgSetDenomMetadata)
+    assert_eq!(2, res.messages.len());
+    match &res.messages[1] {
+      SubMsg { msg: CosmosMsg::Stargate { type_url, value }, .. } => {
+        assert_eq!(type_url, MsgSetDenomMetadata::TYPE_URL);
+        let msg = MsgSetDenomMetadata::decode(value.as_slice()).unwrap();
+        let metadata = msg.metadata.unwrap();
+        let denom = format!("factory/{}/ATOMNTRN", env.contract.address);
+        assert_eq!(msg.sender, env.contract.address.to_string());
+        assert_eq!(metadata.base, denom);
+        assert_eq!(metadata.display, "ATOMNTRN");
+        assert_eq!(metadata.name, "ATOM-NTRN SouLP");
+        assert_eq!(metadata.uri, "https://example.com/logo.png");
+        assert_eq!(metadata.denom_units.len(), 2);
+        assert_eq!(metadata.denom_units[0].denom, denom);
+        assert_eq!(metadata.denom_units[1].exponent, 6);
+      }
+      _ => panic!("Expected Stargate message"),
+    }
     assert_eq!(1, res.attributes.len());
     assert_eq!("method", res.attributes[0].key);
     assert_eq!("instantiate", res.attributes[0].value);
 
     // Check state was saved correctly
     let state = STATE.load(deps.as_ref().storage).unwrap();
-    assert_eq!(state.pool, pool);
+    assert_eq!(state.pool, Token::Native(pool));
+    assert_eq!(state.pair, "pair_address");
+    assert_eq!(state.asset_infos.len(), 2);
     assert_eq!(state.evacuate_address, creator);
     assert_eq!(state.mint_ratio, Decimal::percent(100));
+    assert_eq!(state.subdenom, "ATOMNTRN");
+    assert_eq!(state.owner, Some(creator));
+    assert_eq!(state.pending_owner, None);
+    assert_eq!(TOTALS.load(deps.as_ref().storage).unwrap(), Totals::default());
+  }
+
+  #[test]
+  fn test_instantiate_cw20_lp() {
+    let mut deps = mock_dependencies();
+    MockPair::new("pair_address", "lp_contract", PairType::Xyk {})
+      .with_cw20_lp()
+      .mock(&mut deps.querier);
+
+    let info = mock_info("creator", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, instantiate_msg("pair_address")).unwrap();
+
+    let state = STATE.load(deps.as_ref().storage).unwrap();
+    assert_eq!(state.pool, Token::Cw20("lp_contract".to_string()));
+  }
+
+  #[test]
+  fn test_instantiate_rejects_non_xyk_pair() {
+    let mut deps = mock_dependencies();
+    mock_pair(&mut deps.querier, "pair_address", "pool_token", PairType::Stable {});
+
+    let info = mock_info("creator", &[]);
+    let err = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg("pair_address")).unwrap_err();
+    match err {
+      ContractError::Generic(msg) => assert_eq!(msg, "Pair pair_address is not an XYK pair"),
+      _ => panic!("Expected Generic error"),
+    }
+  }
+
+  #[test]
+  fn test_instantiate_rejects_non_pair() {
+    let mut deps = mock_dependencies();
+    mock_pair(&mut deps.querier, "pair_address", "pool_token", PairType::Xyk {});
+
+    let info = mock_info("creator", &[]);
+    let err = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg("not_a_pair")).unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
+  }
+
+  fn setup_v0_1(deps: DepsMut) {
+    set_contract_version(deps.storage, CONTRACT_NAME, "0.1.0").unwrap();
+    STATE_V0_1.save(deps.storage, &StateV0_1 {
+      pool: "pool_token".to_string(),
+      evacuate_address: "evacuate_addr".to_string(),
+      mint_ratio: Decimal::percent(100),
+    }).unwrap();
+  }
+
+  #[test]
+  fn test_migrate_from_v0_1() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_v0_1(deps.as_mut());
+    deps.querier.update_balance(env.contract.address.clone(), coins(1000, "pool_token"));
+    mock_pair(&mut deps.querier, "pair_address", "pool_token", PairType::Xyk {});
+
+    let res = migrate(deps.as_mut(), env, MigrateMsg {
+      pair: Some("pair_address".to_string()),
+    }).unwrap();
+    assert_eq!(res.attributes[1].value, "0.1.0");
+    assert_eq!(res.attributes[2].value, CONTRACT_VERSION);
+
+    let state = STATE.load(deps.as_ref().storage).unwrap();
+    assert_eq!(state.owner, Some("evacuate_addr".to_string()));
+    assert_eq!(state.pending_owner, None);
+    assert_eq!(state.subdenom, LEGACY_SUBDENOM);
+    assert_eq!(state.pair, "pair_address");
+    assert_eq!(state.pool, Token::Native("pool_token".to_string()));
+
+    let totals = TOTALS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(totals.locked.u128(), 1000);
+    assert_eq!(totals.minted.u128(), 0);
+
+    let version = get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.version, CONTRACT_VERSION);
+  }
+
+  #[test]
+  fn test_migrate_from_v0_1_requires_matching_pair() {
+    let mut deps = mock_dependencies();
+    setup_v0_1(deps.as_mut());
+    mock_pair(&mut deps.querier, "pair_address", "other_pool_token", PairType::Xyk {});
+
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg { pair: None }).unwrap_err();
+    assert!(matches!(err, ContractError::Generic(_)));
+
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {
+      pair: Some("pair_address".to_string()),
+    }).unwrap_err();
+    match err {
+      ContractError::Generic(msg) => assert_eq!(msg, "Pair pair_address does not issue pool_token"),
+      _ => panic!("Expected Generic error"),
+    }
+  }
+
+  #[test]
+  fn test_migrate_refuses_downgrade() {
+    let mut deps = mock_dependencies();
+    setup_v0_1(deps.as_mut());
+    set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg { pair: None }).unwrap_err();
+    assert!(matches!(err, ContractError::Downgrade { .. }));
+  }
+
+  #[test]
+  fn test_migrate_refuses_other_contract() {
+    let mut deps = mock_dependencies();
+    setup_v0_1(deps.as_mut());
+    set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.1.0").unwrap();
+
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg { pair: None }).unwrap_err();
+    assert!(matches!(err, ContractError::ContractMismatch { .. }));
   }
 }
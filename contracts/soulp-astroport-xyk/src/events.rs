@@ -0,0 +1,152 @@
+use cosmwasm_std::{Addr, Event, Uint128};
+
+/// Emitted when LP is locked & SouLP minted. Indexed on chain as `wasm-soulp_deposit`.
+pub struct DepositEvent {
+  pub depositor: Addr,
+  pub recipient: Addr,
+  pub lp_amount: Uint128,
+  pub minted: Uint128,
+  pub total_locked: Uint128,
+  pub total_minted: Uint128,
+}
+
+impl From<DepositEvent> for Event {
+  fn from(event: DepositEvent) -> Event {
+    Event::new("soulp_deposit")
+      .add_attribute("depositor", event.depositor)
+      .add_attribute("recipient", event.recipient)
+      .add_attribute("lp_amount", event.lp_amount)
+      .add_attribute("minted", event.minted)
+      .add_attribute("total_locked", event.total_locked)
+      .add_attribute("total_minted", event.total_minted)
+  }
+}
+
+/// Emitted when LP is locked without minting SouLP. Indexed on chain as `wasm-soulp_donate`.
+pub struct DonateEvent {
+  pub donor: Addr,
+  pub lp_amount: Uint128,
+  pub total_locked: Uint128,
+  pub total_donated: Uint128,
+}
+
+impl From<DonateEvent> for Event {
+  fn from(event: DonateEvent) -> Event {
+    Event::new("soulp_donate")
+      .add_attribute("donor", event.donor)
+      .add_attribute("lp_amount", event.lp_amount)
+      .add_attribute("total_locked", event.total_locked)
+      .add_attribute("total_donated", event.total_donated)
+  }
+}
+
+/// Emitted when orphaned LP is reconciled. Indexed on chain as `wasm-soulp_reconcile`.
+pub struct ReconcileEvent {
+  pub sender: Addr,
+  /// Recipient of the minted SouLP. `None` if the LP was treated as a donation.
+  pub recipient: Option<Addr>,
+  pub lp_amount: Uint128,
+  pub minted: Uint128,
+}
+
+impl From<ReconcileEvent> for Event {
+  fn from(event: ReconcileEvent) -> Event {
+    Event::new("soulp_reconcile")
+      .add_attribute("sender", event.sender)
+      .add_attribute("recipient", event.recipient.map(String::from).unwrap_or_default())
+      .add_attribute("lp_amount", event.lp_amount)
+      .add_attribute("minted", event.minted)
+  }
+}
+
+/// Emitted when SouLP is burned. Indexed on chain as `wasm-soulp_burn`.
+pub struct BurnEvent {
+  pub sender: Addr,
+  pub amount: Uint128,
+  pub total_burned: Uint128,
+  /// SouLP minted & not yet burned after this burn
+  pub outstanding: Uint128,
+}
+
+impl From<BurnEvent> for Event {
+  fn from(event: BurnEvent) -> Event {
+    Event::new("soulp_burn")
+      .add_attribute("sender", event.sender)
+      .add_attribute("amount", event.amount)
+      .add_attribute("total_burned", event.total_burned)
+      .add_attribute("outstanding", event.outstanding)
+  }
+}
+
+/// Emitted when assets are evacuated. Indexed on chain as `wasm-soulp_evacuate`.
+pub struct EvacuateEvent {
+  pub sender: Addr,
+  pub recipient: Addr,
+  /// Kind of the evacuated asset, i.e. `native`, `cw20`, `cw721` or `cw1155`
+  pub asset: String,
+  /// Number of transfer messages issued
+  pub transfers: usize,
+  /// SouLP held by the contract, burned instead of evacuated
+  pub burned: Uint128,
+}
+
+impl From<EvacuateEvent> for Event {
+  fn from(event: EvacuateEvent) -> Event {
+    Event::new("soulp_evacuate")
+      .add_attribute("sender", event.sender)
+      .add_attribute("recipient", event.recipient)
+      .add_attribute("asset", event.asset)
+      .add_attribute("transfers", event.transfers.to_string())
+      .add_attribute("burned", event.burned)
+  }
+}
+
+/// Emitted when the evacuation address changes. Indexed on chain as `wasm-soulp_change_evacuate_address`.
+pub struct ChangeEvacuateAddressEvent {
+  pub old_address: String,
+  pub new_address: String,
+}
+
+impl From<ChangeEvacuateAddressEvent> for Event {
+  fn from(event: ChangeEvacuateAddressEvent) -> Event {
+    Event::new("soulp_change_evacuate_address")
+      .add_attribute("old_address", event.old_address)
+      .add_attribute("new_address", event.new_address)
+  }
+}
+
+/// Emitted when cw20 tracking for `EvacuateAsset::AllCw20` changes. Indexed on chain as
+/// `wasm-soulp_cw20_tracking`.
+pub struct Cw20TrackingEvent {
+  /// One of `receive`, `register` or `unregister`
+  pub action: String,
+  pub token: Addr,
+  /// Whether the token is tracked afterwards
+  pub tracked: bool,
+}
+
+impl From<Cw20TrackingEvent> for Event {
+  fn from(event: Cw20TrackingEvent) -> Event {
+    Event::new("soulp_cw20_tracking")
+      .add_attribute("action", event.action)
+      .add_attribute("token", event.token)
+      .add_attribute("tracked", event.tracked.to_string())
+  }
+}
+
+/// Emitted on ownership changes. Indexed on chain as `wasm-soulp_ownership`.
+pub struct OwnershipEvent {
+  /// One of `transfer`, `accept` or `renounce`
+  pub action: String,
+  pub owner: Option<String>,
+  pub pending_owner: Option<String>,
+}
+
+impl From<OwnershipEvent> for Event {
+  fn from(event: OwnershipEvent) -> Event {
+    Event::new("soulp_ownership")
+      .add_attribute("action", event.action)
+      .add_attribute("owner", event.owner.unwrap_or_default())
+      .add_attribute("pending_owner", event.pending_owner.unwrap_or_default())
+  }
+}
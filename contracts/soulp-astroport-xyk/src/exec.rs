@@ -1,15 +1,19 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{coin, from_json, to_json_binary, Addr, Api, Coin, Decimal, Decimal256, DepsMut, Env, MessageInfo, QuerierWrapper, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg};
 
 use r#impl::execute::{ExecuteContext, Token};
 use r#impl::msg::EvacuateAsset;
-use r#impl::tokenfactory::{self, TFToken};
-
-use crate::contract::SUBDENOM;
-use crate::state::{State, STATE};
+use r#impl::state::CW20_TOKENS;
+use r#impl::tokenfactory::TFToken;
+use cw20::Cw20ReceiveMsg;
+
+use crate::astroport::{optimal_swap_amount, query_total_fee_bps, Asset, AssetInfo, PairExecuteMsg, PairQueryMsg, PoolResponse};
+use crate::events::{BurnEvent, ChangeEvacuateAddressEvent, Cw20TrackingEvent, DepositEvent, DonateEvent, EvacuateEvent, OwnershipEvent, ReconcileEvent};
+use crate::reply::{PROVIDE_AND_LOCK_REPLY_ID, ZAP_IN_REPLY_ID};
+use crate::state::{DepositorInfo, PendingLock, PendingZap, State, Totals, DEPOSITORS, PENDING_LOCK, PENDING_ZAP, STATE, TOTALS};
 use crate::{ContractError, ContractResult};
-use crate::msg::ExecuteMsg;
+use crate::msg::{ExecuteMsg, ReceiveMsg, WeightedRecipient};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
@@ -20,62 +24,703 @@ pub fn execute(
 ) -> ContractResult<Response> {
   let mut ctx = ExecuteContext { deps, env, info };
   match msg {
-    ExecuteMsg::Deposit {} =>
-      deposit(&mut ctx)?,
+    ExecuteMsg::Deposit { recipient, recipients } =>
+      deposit(&mut ctx, recipient, recipients),
+    ExecuteMsg::Receive(msg) =>
+      receive(&mut ctx, msg),
+    ExecuteMsg::ProvideAndLock { slippage_tolerance, recipient } =>
+      provide_and_lock(&mut ctx, slippage_tolerance, recipient),
+    ExecuteMsg::ZapIn { offer_asset, max_spread } =>
+      zap_in(&mut ctx, offer_asset, max_spread),
     ExecuteMsg::Evacuate { asset } =>
-      evacuate(&mut ctx, asset)?,
+      evacuate(&mut ctx, asset),
+    ExecuteMsg::RegisterCw20 { contract } =>
+      register_cw20(&mut ctx, contract),
+    ExecuteMsg::UnregisterCw20 { contract } =>
+      unregister_cw20(&mut ctx, contract),
     ExecuteMsg::ChangeEvacuateAddress { new_address } =>
-      change_evacuate_address(&mut ctx, new_address)?,
-  };
-  Ok(Response::new())
+      change_evacuate_address(&mut ctx, new_address),
+    ExecuteMsg::TransferOwnership { new_owner } =>
+      transfer_ownership(&mut ctx, new_owner),
+    ExecuteMsg::Donate {} =>
+      donate_funds(&mut ctx),
+    ExecuteMsg::ReconcileOrphanedLp { recipient } =>
+      reconcile_orphaned_lp(&mut ctx, recipient),
+    ExecuteMsg::Burn {} =>
+      burn(&mut ctx),
+    ExecuteMsg::AcceptOwnership {} =>
+      accept_ownership(&mut ctx),
+    ExecuteMsg::RenounceOwnership {} =>
+      renounce_ownership(&mut ctx),
+  }
+}
+
+fn deposit(
+  ctx: &mut ExecuteContext,
+  recipient: Option<String>,
+  recipients: Option<Vec<WeightedRecipient>>,
+) -> ContractResult<Response> {
+  let state = STATE.load(ctx.deps.storage)?;
+  let lp_amount = lp_funds(ctx, &state)?;
+  let recipients = resolve_recipients(ctx.deps.api, &ctx.info.sender, recipient, recipients)?;
+  lock(ctx.deps.branch(), &ctx.env, &state, &ctx.info.sender, lp_amount, recipients)
+}
+
+fn donate_funds(ctx: &mut ExecuteContext) -> ContractResult<Response> {
+  let state = STATE.load(ctx.deps.storage)?;
+  let lp_amount = lp_funds(ctx, &state)?;
+  donate(ctx.deps.branch(), &ctx.info.sender, lp_amount)
 }
 
-fn deposit(ctx: &mut ExecuteContext) -> ContractResult<Response> {
+/// Amount of native LP sent along, which must be the only asset.
+fn lp_funds(ctx: &ExecuteContext, state: &State) -> ContractResult<Uint128> {
   if ctx.info.funds.len() != 1 {
     return Err(ContractError::InvalidFunds("Expected exactly one asset".to_string()));
   }
 
-  let state = STATE.load(ctx.deps.storage)?;
-
   let fund = &ctx.info.funds[0];
-  if fund.denom != state.pool {
+  if state.pool != Token::Native(fund.denom.clone()) {
     return Err(ContractError::InvalidFunds("Invalid asset".to_string()));
   }
+  Ok(fund.amount)
+}
+
+/// Deposit of cw20 LP tokens. The cw20 contract is the message sender, while the hook's sender
+/// is the depositor. Other cw20 tokens are accepted & tracked for evacuation, unless sent with a
+/// deposit hook.
+fn receive(ctx: &mut ExecuteContext, msg: Cw20ReceiveMsg) -> ContractResult<Response> {
+  let state = STATE.load(ctx.deps.storage)?;
+  if !ctx.info.funds.is_empty() {
+    return Err(ContractError::InvalidFunds("Unexpected native funds".to_string()));
+  }
+  if state.pool != Token::Cw20(ctx.info.sender.to_string()) {
+    if from_json::<ReceiveMsg>(&msg.msg).is_ok() {
+      return Err(ContractError::InvalidFunds("Invalid asset".to_string()));
+    }
+    // Anyone can call `Receive`, so only track senders actually implementing cw20
+    let tracked = Token::Cw20(ctx.info.sender.to_string())
+      .query_balance(&ctx.deps.querier, &ctx.env.contract.address)
+      .is_ok();
+    if tracked {
+      CW20_TOKENS.save(ctx.deps.storage, &ctx.info.sender, &())?;
+    }
+    return Ok(Response::new()
+      .add_event(Cw20TrackingEvent {
+        action: "receive".to_string(),
+        token: ctx.info.sender.clone(),
+        tracked,
+      }.into())
+    );
+  }
+
+  let depositor = ctx.deps.api.addr_validate(&msg.sender)?;
+  match from_json(&msg.msg)? {
+    ReceiveMsg::Deposit { recipient, recipients } => {
+      let recipients = resolve_recipients(ctx.deps.api, &depositor, recipient, recipients)?;
+      lock(ctx.deps.branch(), &ctx.env, &state, &depositor, msg.amount, recipients)
+    }
+    ReceiveMsg::Donate {} =>
+      donate(ctx.deps.branch(), &depositor, msg.amount),
+  }
+}
+
+fn provide_and_lock(
+  ctx: &mut ExecuteContext,
+  slippage_tolerance: Option<Decimal>,
+  recipient: Option<String>,
+) -> ContractResult<Response> {
+  let state = STATE.load(ctx.deps.storage)?;
+  let recipient = match recipient {
+    Some(recipient) => ctx.deps.api.addr_validate(&recipient)?,
+    None => ctx.info.sender.clone(),
+  };
+
+  let denoms = pool_denoms(&state)?;
+  if ctx.info.funds.len() != denoms.len() {
+    return Err(ContractError::InvalidFunds("Expected both pool assets".to_string()));
+  }
+
+  let deposits = denoms.iter()
+    .map(|denom| {
+      ctx.info.funds.iter()
+        .find(|coin| &coin.denom == denom)
+        .ok_or_else(|| ContractError::InvalidFunds("Expected both pool assets".to_string()))
+    })
+    .collect::<ContractResult<Vec<_>>>()?;
+
+  // Pre-existing balances, i.e. excluding the funds sent along
+  let balances = deposits.into_iter()
+    .map(|deposit| -> ContractResult<_> {
+      let balance = ctx.deps.querier.query_balance(&ctx.env.contract.address, &deposit.denom)?;
+      Ok(coin(balance.amount.checked_sub(deposit.amount)?.u128(), &deposit.denom))
+    })
+    .collect::<ContractResult<Vec<_>>>()?;
+
+  let submsg = provide(
+    ctx.deps.branch(),
+    &ctx.env,
+    &state,
+    ctx.info.sender.clone(),
+    recipient,
+    balances,
+    slippage_tolerance,
+  )?;
+  Ok(Response::new().add_submessage(submsg))
+}
+
+fn zap_in(
+  ctx: &mut ExecuteContext,
+  offer_asset: Asset,
+  max_spread: Option<Decimal>,
+) -> ContractResult<Response> {
+  let state = STATE.load(ctx.deps.storage)?;
+  let denoms = pool_denoms(&state)?;
+  let offer_denom = offer_asset.info.native_denom()
+    .ok_or_else(|| ContractError::InvalidFunds("Offer asset must be native".to_string()))?
+    .to_string();
+  if ctx.info.funds != vec![coin(offer_asset.amount.u128(), &offer_denom)] {
+    return Err(ContractError::InvalidFunds("Expected exactly the offer asset".to_string()));
+  }
+
+  let plan = plan_zap(&ctx.deps.querier, &state, &offer_asset)?;
+
+  // Pre-existing balances, i.e. excluding the funds sent along
+  let balances = denoms.iter()
+    .map(|denom| -> ContractResult<_> {
+      let balance = ctx.deps.querier.query_balance(&ctx.env.contract.address, denom)?;
+      let deposit = if denom == &offer_denom { offer_asset.amount } else { Uint128::zero() };
+      Ok(coin(balance.amount.checked_sub(deposit)?.u128(), denom))
+    })
+    .collect::<ContractResult<Vec<_>>>()?;
+  PENDING_ZAP.save(ctx.deps.storage, &PendingZap {
+    sender: ctx.info.sender.clone(),
+    balances,
+    max_spread,
+  })?;
+
+  Ok(Response::new()
+    .add_submessage(SubMsg::reply_on_success(WasmMsg::Execute {
+      contract_addr: state.pair,
+      msg: to_json_binary(&PairExecuteMsg::Swap {
+        offer_asset: Asset {
+          info: offer_asset.info,
+          amount: plan.swap_amount,
+        },
+        ask_asset_info: Some(plan.ask_asset_info),
+        belief_price: None,
+        max_spread,
+        to: None,
+      })?,
+      funds: vec![coin(plan.swap_amount.u128(), offer_denom)],
+    }, ZAP_IN_REPLY_ID))
+  )
+}
+
+pub(crate) struct ZapPlan {
+  /// Reserves of the offer & ask asset, respectively
+  pub reserves: [Uint128; 2],
+  pub total_share: Uint128,
+  pub ask_asset_info: AssetInfo,
+  pub swap_amount: Uint128,
+}
+
+/// Determine how much of the offer asset to swap, accounting for the pair's fee.
+pub(crate) fn plan_zap(querier: &QuerierWrapper, state: &State, offer_asset: &Asset) -> ContractResult<ZapPlan> {
+  if !state.asset_infos.contains(&offer_asset.info) {
+    return Err(ContractError::InvalidFunds("Offer asset is not a pool asset".to_string()));
+  }
+  let pool: PoolResponse = querier.query_wasm_smart(&state.pair, &PairQueryMsg::Pool {})?;
+  let offer_reserve = pool.assets.iter().find(|asset| asset.info == offer_asset.info);
+  let ask = pool.assets.iter().find(|asset| asset.info != offer_asset.info);
+  let (offer_reserve, ask) = match (offer_reserve, ask) {
+    (Some(offer_reserve), Some(ask)) if !offer_reserve.amount.is_zero() && !ask.amount.is_zero() => (offer_reserve, ask),
+    _ => return Err(ContractError::Generic("Cannot zap into an empty pool".to_string())),
+  };
+
+  let fee_bps = query_total_fee_bps(querier, &state.pair)?;
+  let swap_amount = optimal_swap_amount(offer_asset.amount, offer_reserve.amount, fee_bps);
+  if swap_amount.is_zero() || swap_amount == offer_asset.amount {
+    return Err(ContractError::InvalidFunds("Offer amount is too small".to_string()));
+  }
+
+  Ok(ZapPlan {
+    reserves: [offer_reserve.amount, ask.amount],
+    total_share: pool.total_share,
+    ask_asset_info: ask.info.clone(),
+    swap_amount,
+  })
+}
+
+/// Denoms of the pool assets. Pairs with cw20 assets cannot be provided to through this contract.
+pub(crate) fn pool_denoms(state: &State) -> ContractResult<Vec<String>> {
+  state.asset_infos.iter()
+    .map(|info| info.native_denom().map(str::to_string))
+    .collect::<Option<Vec<_>>>()
+    .ok_or_else(|| ContractError::Generic("Pairs with cw20 assets are not supported".to_string()))
+}
+
+/// Provide the pool assets held by the contract in excess of the pre-existing `balances` as
+/// liquidity, balanced to the pool ratio. The reply then locks the received LP on behalf of the
+/// `recipient` & refunds any unused assets to the `sender`.
+pub(crate) fn provide(
+  deps: DepsMut,
+  env: &Env,
+  state: &State,
+  sender: Addr,
+  recipient: Addr,
+  balances: Vec<Coin>,
+  slippage_tolerance: Option<Decimal>,
+) -> ContractResult<SubMsg> {
+  let contract = env.contract.address.clone();
+  let deposits = balances.iter()
+    .map(|balance| -> ContractResult<_> {
+      let current = deps.querier.query_balance(&contract, &balance.denom)?;
+      Ok(current.amount.checked_sub(balance.amount)?)
+    })
+    .collect::<ContractResult<Vec<_>>>()?;
+
+  let pool: PoolResponse = deps.querier.query_wasm_smart(&state.pair, &PairQueryMsg::Pool {})?;
+  let reserves: Vec<_> = pool.assets.iter().map(|asset| asset.amount).collect();
+  let amounts = balanced_amounts(&deposits, &reserves);
+
+  let assets: Vec<Asset> = state.asset_infos.iter().zip(&amounts)
+    .map(|(info, amount)| Asset { info: info.clone(), amount: *amount })
+    .collect();
+  let mut funds: Vec<Coin> = balances.iter().zip(&amounts)
+    .filter(|(_, amount)| !amount.is_zero())
+    .map(|(balance, amount)| coin(amount.u128(), &balance.denom))
+    .collect();
+  funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+  // Remember the LP balance to measure the received LP in the reply
+  let lp_balance = state.pool.query_balance(&deps.querier, &contract)?;
+  PENDING_LOCK.save(deps.storage, &PendingLock {
+    sender,
+    recipient,
+    lp_balance,
+    balances,
+  })?;
+
+  Ok(SubMsg::reply_on_success(WasmMsg::Execute {
+    contract_addr: state.pair.clone(),
+    msg: to_json_binary(&PairExecuteMsg::ProvideLiquidity {
+      assets,
+      slippage_tolerance,
+      auto_stake: Some(false),
+      receiver: None,
+    })?,
+    funds,
+  }, PROVIDE_AND_LOCK_REPLY_ID))
+}
+
+/// Portion of the `deposits` matching the ratio of the pool `reserves`, such that no assets are
+/// donated to the pool. Empty pools accept any ratio.
+fn balanced_amounts(deposits: &[Uint128], reserves: &[Uint128]) -> Vec<Uint128> {
+  if reserves.len() != 2 || deposits.len() != 2 || reserves.iter().any(|reserve| reserve.is_zero()) {
+    return deposits.to_vec();
+  }
+  let (d0, d1) = (deposits[0], deposits[1]);
+  let (r0, r1) = (reserves[0], reserves[1]);
+  if d0.full_mul(r1) > d1.full_mul(r0) {
+    vec![d1.multiply_ratio(r0, r1), d1]
+  } else {
+    vec![d0, d0.multiply_ratio(r1, r0)]
+  }
+}
+
+/// Resolve the recipients of a deposit, defaulting to the depositor.
+fn resolve_recipients(
+  api: &dyn Api,
+  depositor: &Addr,
+  recipient: Option<String>,
+  recipients: Option<Vec<WeightedRecipient>>,
+) -> ContractResult<Vec<(Addr, u64)>> {
+  match (recipient, recipients) {
+    (Some(_), Some(_)) =>
+      Err(ContractError::Generic("Cannot specify both recipient and recipients".to_string())),
+    (None, None) =>
+      Ok(vec![(depositor.clone(), 1)]),
+    (Some(recipient), None) =>
+      Ok(vec![(api.addr_validate(&recipient)?, 1)]),
+    (None, Some(recipients)) => {
+      if recipients.is_empty() {
+        return Err(ContractError::Generic("Recipients must not be empty".to_string()));
+      }
+      recipients.into_iter()
+        .map(|recipient| {
+          if recipient.weight == 0 {
+            return Err(ContractError::Generic("Recipient weight must not be zero".to_string()));
+          }
+          Ok((api.addr_validate(&recipient.address)?, recipient.weight))
+        })
+        .collect()
+    }
+  }
+}
+
+pub(crate) struct DepositPlan {
+  pub mint_amount: Uint128,
+  /// Totals after the deposit
+  pub totals: Totals,
+  /// Depositor after the deposit
+  pub depositor: DepositorInfo,
+}
+
+/// SouLP minted for & totals after locking `lp_amount` LP. Shared by deposits & their simulation.
+///
+/// The fractional SouLP which cannot be minted is carried over to the depositor's next deposit,
+/// such that repeated small deposits are not taxed by rounding.
+pub(crate) fn plan_deposit(
+  state: &State,
+  mut totals: Totals,
+  mut depositor: DepositorInfo,
+  lp_amount: Uint128,
+) -> ContractResult<DepositPlan> {
+  let exact = Decimal256::from_ratio(lp_amount, 1u128)
+    .checked_mul(Decimal256::from(state.mint_ratio))?
+    .checked_add(depositor.remainder)?;
+  let mint_amount = Uint128::try_from(exact.to_uint_floor()).map_err(StdError::from)?;
+  if mint_amount.is_zero() {
+    return Err(ContractError::InvalidFunds("Deposit is too small to mint any SouLP".to_string()));
+  }
+
+  totals.locked = totals.locked.checked_add(lp_amount)?;
+  totals.minted = totals.minted.checked_add(mint_amount)?;
+  ensure_mint_bound(state, &totals)?;
+
+  depositor.locked = depositor.locked.checked_add(lp_amount)?;
+  depositor.minted = depositor.minted.checked_add(mint_amount)?;
+  depositor.remainder = exact - exact.floor();
+  Ok(DepositPlan { mint_amount, totals, depositor })
+}
+
+/// Lock `lp_amount` LP on behalf of `depositor` & mint the corresponding SouLP, split across the
+/// weighted `recipients`. Rounding dust is attributed to the last recipient.
+pub(crate) fn lock(
+  deps: DepsMut,
+  env: &Env,
+  state: &State,
+  depositor: &Addr,
+  lp_amount: Uint128,
+  recipients: Vec<(Addr, u64)>,
+) -> ContractResult<Response> {
+  let totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+  let info = DEPOSITORS.may_load(deps.storage, depositor)?.unwrap_or_default();
+  let DepositPlan { mint_amount, totals, depositor: info } = plan_deposit(state, totals, info, lp_amount)?;
+  ensure_invariant(&deps.querier, &env.contract.address, state, &totals, mint_amount)?;
+  TOTALS.save(deps.storage, &totals)?;
+  DEPOSITORS.save(deps.storage, depositor, &info)?;
+
+  let token = state.token(env.contract.address.clone());
+  let total_weight: u128 = recipients.iter().map(|(_, weight)| *weight as u128).sum();
+  let count = recipients.len();
+
+  let mut response = Response::new();
+  let mut lp_remaining = lp_amount;
+  let mut mint_remaining = mint_amount;
+  for (i, (recipient, weight)) in recipients.into_iter().enumerate() {
+    let (lp_share, mint_share) = if i + 1 == count {
+      (lp_remaining, mint_remaining)
+    } else {
+      (lp_amount.multiply_ratio(weight, total_weight), mint_amount.multiply_ratio(weight, total_weight))
+    };
+    lp_remaining -= lp_share;
+    mint_remaining -= mint_share;
+
+    // NOTE: if this is a non-standard TokenFactory we may need to adjust the messages here
+    if !mint_share.is_zero() {
+      response = response.add_messages(token.mint(mint_share, recipient.to_string()));
+    }
+    response = response.add_event(DepositEvent {
+      depositor: depositor.clone(),
+      recipient,
+      lp_amount: lp_share,
+      minted: mint_share,
+      total_locked: totals.locked,
+      total_minted: totals.minted,
+    }.into());
+  }
+  Ok(response)
+}
+
+/// Lock the LP held in excess of the recorded total, e.g. from plain bank sends.
+fn reconcile_orphaned_lp(ctx: &mut ExecuteContext, recipient: Option<String>) -> ContractResult<Response> {
+  let state = STATE.load(ctx.deps.storage)?;
+  ensure_owner(&state, &ctx.info.sender)?;
+  let recipient = recipient.map(|recipient| ctx.deps.api.addr_validate(&recipient)).transpose()?;
+
+  let mut totals = TOTALS.may_load(ctx.deps.storage)?.unwrap_or_default();
+  let balance = state.pool.query_balance(&ctx.deps.querier, &ctx.env.contract.address)?;
+  let orphaned = balance.saturating_sub(totals.locked);
+  if orphaned.is_zero() {
+    return Err(ContractError::Generic("No orphaned LP to reconcile".to_string()));
+  }
+
+  let (response, minted) = match &recipient {
+    Some(recipient) => {
+      let response = lock(ctx.deps.branch(), &ctx.env, &state, recipient, orphaned, vec![(recipient.clone(), 1)])?;
+      let minted = TOTALS.load(ctx.deps.storage)?.minted - totals.minted;
+      (response, minted)
+    }
+    None => {
+      // The sender of orphaned LP is unknown, hence not attributed to any depositor
+      totals.locked = totals.locked.checked_add(orphaned)?;
+      totals.donated = totals.donated.checked_add(orphaned)?;
+      TOTALS.save(ctx.deps.storage, &totals)?;
+      (Response::new(), Uint128::zero())
+    }
+  };
+
+  Ok(response
+    .add_event(ReconcileEvent {
+      sender: ctx.info.sender.clone(),
+      recipient,
+      lp_amount: orphaned,
+      minted,
+    }.into())
+  )
+}
+
+/// Burn the SouLP sent along. The locked LP remains, raising the backing of the outstanding SouLP.
+fn burn(ctx: &mut ExecuteContext) -> ContractResult<Response> {
+  let state = STATE.load(ctx.deps.storage)?;
+  let token = state.token(ctx.env.contract.address.clone());
+  let amount = match ctx.info.funds.as_slice() {
+    [fund] if fund.denom == token.denom() && !fund.amount.is_zero() => fund.amount,
+    _ => return Err(ContractError::InvalidFunds("Expected only SouLP".to_string())),
+  };
+
+  let mut totals = TOTALS.may_load(ctx.deps.storage)?.unwrap_or_default();
+  totals.burned = totals.burned.checked_add(amount)?;
+  TOTALS.save(ctx.deps.storage, &totals)?;
+
+  Ok(Response::new()
+    .add_messages(token.burn(amount))
+    .add_event(BurnEvent {
+      sender: ctx.info.sender.clone(),
+      amount,
+      total_burned: totals.burned,
+      outstanding: totals.outstanding(),
+    }.into())
+  )
+}
+
+/// Ensure no more SouLP has been minted than the locked LP permits.
+fn ensure_mint_bound(state: &State, totals: &Totals) -> ContractResult<()> {
+  let max_mintable = totals.max_mintable(state.mint_ratio);
+  if totals.minted > max_mintable {
+    return Err(ContractError::InvariantViolated(format!(
+      "{} SouLP minted, but {} LP only permit {}", totals.minted, totals.locked, max_mintable,
+    )));
+  }
+  Ok(())
+}
 
-  let mint_amount = fund.amount * state.mint_ratio;
+/// Ensure the contract actually holds the recorded locked LP & no SouLP circulates beyond the
+/// recorded totals once the pending `mint_amount` is minted.
+fn ensure_invariant(
+  querier: &QuerierWrapper,
+  contract: &Addr,
+  state: &State,
+  totals: &Totals,
+  mint_amount: Uint128,
+) -> ContractResult<()> {
+  let balance = state.pool.query_balance(querier, contract)?;
+  if balance < totals.locked {
+    return Err(ContractError::InvariantViolated(format!(
+      "Contract holds {} LP, but {} are recorded as locked", balance, totals.locked,
+    )));
+  }
+  // SouLP burned outside of this contract only raises the backing, hence is tolerated
+  let supply = state.token(contract.clone()).supply(querier)?;
+  if supply.checked_add(mint_amount)? > totals.outstanding() {
+    return Err(ContractError::InvariantViolated(format!(
+      "SouLP supply {} plus {} minted exceeds {} outstanding", supply, mint_amount, totals.outstanding(),
+    )));
+  }
+  Ok(())
+}
 
-  let token = tokenfactory::osmosis::TFToken::new(ctx.env.contract.address.clone(), SUBDENOM.to_string());
+/// Lock `lp_amount` LP donated by `donor` without minting SouLP.
+fn donate(deps: DepsMut, donor: &Addr, lp_amount: Uint128) -> ContractResult<Response> {
+  let mut totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+  totals.locked = totals.locked.checked_add(lp_amount)?;
+  totals.donated = totals.donated.checked_add(lp_amount)?;
+  TOTALS.save(deps.storage, &totals)?;
+
+  DEPOSITORS.update(deps.storage, donor, |info| -> StdResult<_> {
+    let mut info = info.unwrap_or_default();
+    info.donated = info.donated.checked_add(lp_amount)?;
+    Ok(info)
+  })?;
 
-  // NOTE: if this is a non-standard TokenFactory we may need to adjust the messages here
   Ok(Response::new()
-    .add_messages(token.mint(mint_amount, ctx.info.sender.to_string()))
+    .add_event(DonateEvent {
+      donor: donor.clone(),
+      lp_amount,
+      total_locked: totals.locked,
+      total_donated: totals.donated,
+    }.into())
   )
 }
 
 fn evacuate(ctx: &mut ExecuteContext, asset: EvacuateAsset) -> ContractResult<Response> {
   let state = STATE.load(ctx.deps.storage)?;
   let evacuate_address = ctx.deps.api.addr_validate(&state.evacuate_address)?;
+  let kind = match asset {
+    EvacuateAsset::Native { .. } => "native",
+    EvacuateAsset::Cw20 { .. } | EvacuateAsset::AllCw20 { .. } => "cw20",
+    EvacuateAsset::Cw721 { .. } => "cw721",
+    EvacuateAsset::Cw1155 { .. } => "cw1155",
+  };
+  let token = state.token(ctx.env.contract.address.clone());
+  let evacuation = r#impl::execute::evacuate(
+    ctx,
+    state.pool,
+    &token,
+    asset,
+    evacuate_address.clone(),
+  )?;
+
+  // SouLP held by the contract is retired rather than evacuated
+  if !evacuation.burned.is_zero() {
+    let mut totals = TOTALS.may_load(ctx.deps.storage)?.unwrap_or_default();
+    totals.burned = totals.burned.checked_add(evacuation.burned)?;
+    TOTALS.save(ctx.deps.storage, &totals)?;
+  }
+
   Ok(Response::new()
-    .add_messages(r#impl::execute::evacuate(
-      ctx,
-      Token::Native(state.pool),
-      asset,
-      evacuate_address
-    )?)
+    .add_event(EvacuateEvent {
+      sender: ctx.info.sender.clone(),
+      recipient: evacuate_address,
+      asset: kind.to_string(),
+      transfers: evacuation.transfers.len(),
+      burned: evacuation.burned,
+    }.into())
+    .add_messages(evacuation.transfers)
+    .add_messages(evacuation.burn)
     .add_attribute("action", "evacuate")
   )
 }
 
+/// Track a cw20 contract for evacuation. Can only be called by the evacuation address.
+fn register_cw20(ctx: &mut ExecuteContext, contract: String) -> ContractResult<Response> {
+  let state = STATE.load(ctx.deps.storage)?;
+  if state.evacuate_address != ctx.info.sender {
+    return Err(ContractError::Unauthorized {});
+  }
+  let contract = ctx.deps.api.addr_validate(&contract)?;
+  if state.pool == Token::Cw20(contract.to_string()) {
+    return Err(ContractError::InvalidFunds("Cannot evacuate the pool token".to_string()));
+  }
+  // Ensure the contract implements cw20
+  Token::Cw20(contract.to_string()).query_balance(&ctx.deps.querier, &ctx.env.contract.address)?;
+
+  CW20_TOKENS.save(ctx.deps.storage, &contract, &())?;
+  Ok(Response::new()
+    .add_event(Cw20TrackingEvent {
+      action: "register".to_string(),
+      token: contract,
+      tracked: true,
+    }.into())
+  )
+}
+
+/// Stop tracking a cw20 contract, e.g. one failing transfers. Can only be called by the
+/// evacuation address.
+fn unregister_cw20(ctx: &mut ExecuteContext, contract: String) -> ContractResult<Response> {
+  let state = STATE.load(ctx.deps.storage)?;
+  if state.evacuate_address != ctx.info.sender {
+    return Err(ContractError::Unauthorized {});
+  }
+  let contract = ctx.deps.api.addr_validate(&contract)?;
+  CW20_TOKENS.remove(ctx.deps.storage, &contract);
+  Ok(Response::new()
+    .add_event(Cw20TrackingEvent {
+      action: "unregister".to_string(),
+      token: contract,
+      tracked: false,
+    }.into())
+  )
+}
+
 fn change_evacuate_address(ctx: &mut ExecuteContext, new_address: String) -> ContractResult<Response> {
   let state = STATE.load(ctx.deps.storage)?;
+  if state.owner.is_none() {
+    return Err(ContractError::Renounced {});
+  }
   if state.evacuate_address != ctx.info.sender {
     return Err(ContractError::Unauthorized {});
   }
+  let old_address = state.evacuate_address.clone();
   STATE.save(ctx.deps.storage, &State {
-    evacuate_address: new_address,
+    evacuate_address: new_address.clone(),
     ..state
   })?;
-  Ok(Response::new())
+  Ok(Response::new()
+    .add_event(ChangeEvacuateAddressEvent {
+      old_address,
+      new_address,
+    }.into())
+  )
+}
+
+/// Ensure the sender is the current owner. Fails once ownership has been renounced.
+fn ensure_owner(state: &State, sender: &Addr) -> ContractResult<()> {
+  match &state.owner {
+    None => Err(ContractError::Renounced {}),
+    Some(owner) if owner != sender => Err(ContractError::Unauthorized {}),
+    Some(_) => Ok(()),
+  }
+}
+
+fn transfer_ownership(ctx: &mut ExecuteContext, new_owner: String) -> ContractResult<Response> {
+  let mut state = STATE.load(ctx.deps.storage)?;
+  ensure_owner(&state, &ctx.info.sender)?;
+  let new_owner = ctx.deps.api.addr_validate(&new_owner)?;
+  state.pending_owner = Some(new_owner.to_string());
+  STATE.save(ctx.deps.storage, &state)?;
+  Ok(Response::new()
+    .add_event(OwnershipEvent {
+      action: "transfer".to_string(),
+      owner: state.owner,
+      pending_owner: state.pending_owner,
+    }.into())
+  )
+}
+
+fn accept_ownership(ctx: &mut ExecuteContext) -> ContractResult<Response> {
+  let mut state = STATE.load(ctx.deps.storage)?;
+  if state.owner.is_none() {
+    return Err(ContractError::Renounced {});
+  }
+  if state.pending_owner.as_deref() != Some(ctx.info.sender.as_str()) {
+    return Err(ContractError::Unauthorized {});
+  }
+  state.owner = state.pending_owner.take();
+  STATE.save(ctx.deps.storage, &state)?;
+  Ok(Response::new()
+    .add_event(OwnershipEvent {
+      action: "accept".to_string(),
+      owner: state.owner,
+      pending_owner: None,
+    }.into())
+  )
+}
+
+fn renounce_ownership(ctx: &mut ExecuteContext) -> ContractResult<Response> {
+  let mut state = STATE.load(ctx.deps.storage)?;
+  ensure_owner(&state, &ctx.info.sender)?;
+  state.owner = None;
+  state.pending_owner = None;
+  STATE.save(ctx.deps.storage, &state)?;
+  Ok(Response::new()
+    .add_event(OwnershipEvent {
+      action: "renounce".to_string(),
+      owner: None,
+      pending_owner: None,
+    }.into())
+  )
 }
 
 #[cfg(test)]
@@ -83,24 +728,53 @@ mod test {
   use std::marker::PhantomData;
 
   use super::*;
-  use r#impl::tokenfactory::osmosis::MsgMint;
+  use r#impl::cw1155::{BatchBalanceResponse, Cw1155ExecuteMsg, Cw1155QueryMsg};
+  use r#impl::tokenfactory::osmosis::{MsgBurn, MsgMint};
 
-  use cosmwasm_std::{coin, coins, BankMsg, CosmosMsg, DepsMut, Empty, OwnedDeps, SubMsg, WasmMsg, Decimal};
+  use cosmwasm_std::{coins, BankMsg, Binary, CosmosMsg, Empty, OwnedDeps, Reply, SubMsgResponse, SubMsgResult};
   use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockStorage};
   use cw20::Cw20ExecuteMsg;
-  use cw721::Cw721ExecuteMsg;
+  use cosmwasm_std::{SystemError, SystemResult, WasmQuery};
+  use cw721::{Cw721ExecuteMsg, Cw721QueryMsg, OwnerOfResponse, TokensResponse};
   use prost::Message;
   use test_utils::mock_querier::MockQuerier;
+  use crate::astroport::{AssetInfo, PairType};
+  use crate::astroport::testing::MockPair;
+  use crate::reply::reply;
 
   fn setup_test_state(deps: &mut DepsMut) {
     let state = State {
-      pool: "pool_token".to_string(),
+      pool: Token::Native("pool_token".to_string()),
+      pair: "pair".to_string(),
+      asset_infos: vec![
+        AssetInfo::NativeToken { denom: "uatom".to_string() },
+        AssetInfo::NativeToken { denom: "untrn".to_string() },
+      ],
       evacuate_address: "evacuate_addr".to_string(),
       mint_ratio: Decimal::percent(100),
+      subdenom: "SouLP".to_string(),
+      owner: Some("owner".to_string()),
+      pending_owner: None,
     };
     STATE.save(deps.storage, &state).unwrap();
   }
 
+  /// Mock the chain state a deposit of `lp_amount` LP sees: the contract holds the recorded locked LP
+  /// plus the deposit & the outstanding SouLP is in circulation.
+  fn mock_lock(deps: &mut OwnedDeps<MockStorage, MockApi, cosmwasm_std::testing::MockQuerier>, env: &Env, lp_amount: u128) {
+    let state = STATE.load(&deps.storage).unwrap();
+    let totals = TOTALS.may_load(&deps.storage).unwrap().unwrap_or_default();
+    let balance = totals.locked.u128() + lp_amount;
+    match state.pool {
+      Token::Native(denom) => {
+        deps.querier.update_balance(env.contract.address.clone(), coins(balance, denom));
+      }
+      Token::Cw20(lp_contract) => mock_cw20s(&mut deps.querier, &[&lp_contract], balance),
+    }
+    let denom = format!("factory/{}/{}", env.contract.address, state.subdenom);
+    deps.querier.update_balance("holders", coins(totals.outstanding().u128(), denom));
+  }
+
   #[test]
   fn test_evacuate_native_assets() {
     let mut deps = mock_dependencies();
@@ -119,23 +793,18 @@ mod test {
     );
 
     let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info };
-    let result = evacuate(&mut ctx, EvacuateAsset::Native {}).unwrap();
+    let result = evacuate(&mut ctx, EvacuateAsset::Native { denoms: None, exclude: None, amounts: None, start_after: None, limit: None }).unwrap();
 
-    // Should have 2 messages (uatom and uosmo, but not pool_token)
-    assert_eq!(result.messages.len(), 2);
+    // Should have 1 message sending uatom and uosmo, but not pool_token
+    assert_eq!(result.messages.len(), 1);
 
-    // Check that the messages are BankMsg::Send to evacuate_address
-    for msg in result.messages {
-      match msg {
-        SubMsg { msg: CosmosMsg::Bank(BankMsg::Send { to_address, amount }), .. } => {
-          assert_eq!(to_address, "evacuate_addr");
-          assert_eq!(amount.len(), 1);
-          let coin = &amount[0];
-          assert!(coin.denom != "pool_token");
-          assert!(coin.denom == "uatom" || coin.denom == "uosmo");
-        }
-        _ => panic!("Expected BankMsg::Send"),
+    // Check that the message is a BankMsg::Send to evacuate_address
+    match &result.messages[0] {
+      SubMsg { msg: CosmosMsg::Bank(BankMsg::Send { to_address, amount }), .. } => {
+        assert_eq!(to_address, "evacuate_addr");
+        assert_eq!(amount, &vec![coin(100, "uatom"), coin(50, "uosmo")]);
       }
+      _ => panic!("Expected BankMsg::Send"),
     }
 
     // Check attributes
@@ -163,6 +832,7 @@ mod test {
     let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info };
     let result = evacuate(&mut ctx, EvacuateAsset::Cw20 {
       contract: cw20_contract.to_string(),
+      amount: None,
     }).unwrap();
 
     // Should have 1 message
@@ -188,6 +858,194 @@ mod test {
     }
   }
 
+  #[test]
+  fn test_evacuate_native_filtered() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    deps.querier.update_balance(
+      env.contract.address.clone(),
+      vec![coin(100, "uatom"), coin(50, "uosmo"), coin(20, "udispute")],
+    );
+
+    let sent = |result: Response| -> Vec<Coin> {
+      result.messages.into_iter()
+        .flat_map(|msg| match msg.msg {
+          CosmosMsg::Bank(BankMsg::Send { amount, .. }) => amount,
+          _ => panic!("Expected BankMsg::Send"),
+        })
+        .collect()
+    };
+
+    // Skip the disputed denom & leave some uatom dust
+    let mut ctx = ExecuteContext { deps: deps.as_mut(), env: env.clone(), info: mock_info("sender", &[]) };
+    let result = evacuate(&mut ctx, EvacuateAsset::Native {
+      denoms: None,
+      exclude: Some(vec!["udispute".to_string()]),
+      amounts: Some(coins(90, "uatom")),
+      start_after: None,
+      limit: None,
+    }).unwrap();
+    assert_eq!(sent(result), vec![coin(90, "uatom"), coin(50, "uosmo")]);
+
+    let mut ctx = ExecuteContext { deps: deps.as_mut(), env: env.clone(), info: mock_info("sender", &[]) };
+    let result = evacuate(&mut ctx, EvacuateAsset::Native {
+      denoms: Some(vec!["uosmo".to_string()]),
+      exclude: None,
+      amounts: None,
+      start_after: None,
+      limit: None,
+    }).unwrap();
+    assert_eq!(sent(result), coins(50, "uosmo"));
+  }
+
+  #[test]
+  fn test_evacuate_native_paginated() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    deps.querier.update_balance(
+      env.contract.address.clone(),
+      vec![coin(1, "uairdrop1"), coin(2, "uairdrop2"), coin(3, "uairdrop3"), coin(4, "uairdrop4"), coin(5, "uatom")],
+    );
+
+    let mut pages = vec![];
+    let mut start_after = None;
+    loop {
+      let mut ctx = ExecuteContext { deps: deps.as_mut(), env: env.clone(), info: mock_info("sender", &[]) };
+      let result = evacuate(&mut ctx, EvacuateAsset::Native {
+        denoms: None,
+        exclude: None,
+        amounts: None,
+        start_after: start_after.clone(),
+        limit: Some(2),
+      }).unwrap();
+      let Some(SubMsg { msg: CosmosMsg::Bank(BankMsg::Send { amount, .. }), .. }) = result.messages.first() else {
+        break;
+      };
+      start_after = amount.last().map(|coin| coin.denom.clone());
+      pages.push(amount.iter().map(|coin| coin.denom.as_str()).collect::<Vec<_>>().join(","));
+    }
+    assert_eq!(pages, vec!["uairdrop1,uairdrop2", "uairdrop3,uairdrop4", "uatom"]);
+  }
+
+  #[test]
+  fn test_evacuate_native_limit_capped() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    deps.querier.update_balance(
+      env.contract.address.clone(),
+      (0..150).map(|i| coin(1, format!("uairdrop{:03}", i))).collect::<Vec<_>>(),
+    );
+
+    for limit in [None, Some(1000)] {
+      let mut ctx = ExecuteContext { deps: deps.as_mut(), env: env.clone(), info: mock_info("sender", &[]) };
+      let result = evacuate(&mut ctx, EvacuateAsset::Native { denoms: None, exclude: None, amounts: None, start_after: None, limit }).unwrap();
+      match &result.messages[0].msg {
+        CosmosMsg::Bank(BankMsg::Send { amount, .. }) => {
+          assert_eq!(amount.len(), 100);
+          assert_eq!(amount.last().unwrap().denom, "uairdrop099");
+        }
+        _ => panic!("Expected BankMsg::Send"),
+      }
+    }
+  }
+
+  #[test]
+  fn test_evacuate_native_filtered_invalid() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    deps.querier.update_balance(
+      env.contract.address.clone(),
+      vec![coin(100, "uatom"), coin(100, "pool_token")],
+    );
+
+    let invalid = vec![
+      // Not held
+      (Some(vec!["uosmo".to_string()]), None),
+      // The pool token is never evacuable
+      (Some(vec!["pool_token".to_string()]), None),
+      (None, Some(coins(1, "pool_token"))),
+      // Exceeds the balance
+      (None, Some(coins(101, "uatom"))),
+      (None, Some(vec![coin(0, "uatom")])),
+      // Duplicate denoms
+      (None, Some(vec![coin(10, "uatom"), coin(20, "uatom")])),
+      // Outside the selected denoms
+      (Some(vec![]), Some(coins(1, "uatom"))),
+    ];
+    for (denoms, amounts) in invalid {
+      let mut ctx = ExecuteContext { deps: deps.as_mut(), env: env.clone(), info: mock_info("sender", &[]) };
+      let err = evacuate(&mut ctx, EvacuateAsset::Native {
+        denoms,
+        exclude: None,
+        amounts,
+        start_after: None,
+        limit: None,
+      }).unwrap_err();
+      assert!(matches!(err, ContractError::InvalidFunds(_)));
+    }
+  }
+
+  #[test]
+  fn test_evacuate_cw20_partial() {
+    let env = mock_env();
+    let mut deps = OwnedDeps {
+      custom_query_type: PhantomData::<Empty>,
+      querier: MockQuerier::new().with_cw20_balance(env.contract.address.as_str(), 1000),
+      storage: MockStorage::default(),
+      api: MockApi::default(),
+    };
+    setup_test_state(&mut deps.as_mut());
+
+    let mut ctx = ExecuteContext { deps: deps.as_mut(), env: env.clone(), info: mock_info("sender", &[]) };
+    let result = evacuate(&mut ctx, EvacuateAsset::Cw20 {
+      contract: "cw20_contract_addr".to_string(),
+      amount: Some(Uint128::new(400)),
+    }).unwrap();
+    match &result.messages[0].msg {
+      CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+        let msg: Cw20ExecuteMsg = cosmwasm_std::from_json(msg).unwrap();
+        assert_eq!(msg, Cw20ExecuteMsg::Transfer { recipient: "evacuate_addr".to_string(), amount: Uint128::new(400) });
+      }
+      _ => panic!("Expected WasmMsg::Execute"),
+    }
+
+    let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info: mock_info("sender", &[]) };
+    let err = evacuate(&mut ctx, EvacuateAsset::Cw20 {
+      contract: "cw20_contract_addr".to_string(),
+      amount: Some(Uint128::new(1001)),
+    }).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidFunds(_)));
+  }
+
+  /// Mock a cw721 contract holding the given tokens as `(token_id, owner)`
+  fn mock_cw721(querier: &mut cosmwasm_std::testing::MockQuerier, contract: &str, tokens: Vec<(String, String)>) {
+    let contract = contract.to_string();
+    querier.update_wasm(move |query| match query {
+      WasmQuery::Smart { contract_addr, msg } if contract_addr == &contract => {
+        let res = match cosmwasm_std::from_json(msg).unwrap() {
+          Cw721QueryMsg::OwnerOf { token_id, .. } => match tokens.iter().find(|(id, _)| id == &token_id) {
+            Some((_, owner)) => to_json_binary(&OwnerOfResponse { owner: owner.clone(), approvals: vec![] }),
+            None => return SystemResult::Ok(cosmwasm_std::ContractResult::Err("Token not found".to_string())),
+          },
+          Cw721QueryMsg::Tokens { owner, start_after, limit } => to_json_binary(&TokensResponse {
+            tokens: tokens.iter()
+              .filter(|(id, token_owner)| token_owner == &owner && start_after.as_ref().is_none_or(|start| id > start))
+              .map(|(id, _)| id.clone())
+              .take(limit.unwrap_or(10).min(30) as usize)
+              .collect(),
+          }),
+          _ => panic!("Unexpected cw721 query"),
+        };
+        SystemResult::Ok(cosmwasm_std::ContractResult::Ok(res.unwrap()))
+      }
+      _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: format!("{:?}", query) }),
+    });
+  }
+
   #[test]
   fn test_evacuate_cw721_assets() {
     let mut deps = mock_dependencies();
@@ -197,11 +1055,15 @@ mod test {
 
     let cw721_contract = "cw721_contract_addr";
     let token_ids = vec!["token1".to_string(), "token2".to_string(), "token3".to_string()];
+    let owner = env.contract.address.to_string();
+    mock_cw721(&mut deps.querier, cw721_contract, token_ids.iter().map(|id| (id.clone(), owner.clone())).collect());
 
     let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info };
     let result = evacuate(&mut ctx, EvacuateAsset::Cw721 {
       contract: cw721_contract.to_string(),
-      token_ids: token_ids.clone(),
+      token_ids: Some(token_ids.clone()),
+      start_after: None,
+      limit: None,
     }).unwrap();
 
     // Should have 3 messages (one for each token)
@@ -229,33 +1091,196 @@ mod test {
     }
   }
 
+  fn transferred_nfts(result: &Response) -> Vec<String> {
+    result.messages.iter()
+      .map(|msg| match &msg.msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match cosmwasm_std::from_json(msg).unwrap() {
+          Cw721ExecuteMsg::TransferNft { token_id, .. } => token_id,
+          _ => panic!("Expected TransferNft message"),
+        },
+        _ => panic!("Expected WasmMsg::Execute"),
+      })
+      .collect()
+  }
+
   #[test]
-  fn test_evacuate_cw20_invalid_contract() {
+  fn test_evacuate_cw721_skips_unowned() {
     let mut deps = mock_dependencies();
     let env = mock_env();
-    let info = mock_info("sender", &[]);
     setup_test_state(&mut deps.as_mut());
+    let owner = env.contract.address.to_string();
+    mock_cw721(&mut deps.querier, "cw721_contract_addr", vec![
+      ("owned".to_string(), owner),
+      ("other".to_string(), "someone_else".to_string()),
+    ]);
 
-    let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info };
-    let result = evacuate(&mut ctx, EvacuateAsset::Cw20 {
-      contract: "invalid_address".to_string(),
-    });
+    let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info: mock_info("sender", &[]) };
+    let result = evacuate(&mut ctx, EvacuateAsset::Cw721 {
+      contract: "cw721_contract_addr".to_string(),
+      token_ids: Some(vec!["owned".to_string(), "other".to_string()]),
+      start_after: None,
+      limit: None,
+    }).unwrap();
+    assert_eq!(transferred_nfts(&result), vec!["owned"]);
+
+    // Failing ownership queries are not mistaken for unowned tokens
+    let failing = vec![
+      ("cw721_contract_addr", "missing"),
+      ("not_cw721_contract", "owned"),
+    ];
+    for (contract, token_id) in failing {
+      let mut ctx = ExecuteContext { deps: deps.as_mut(), env: mock_env(), info: mock_info("sender", &[]) };
+      let err = evacuate(&mut ctx, EvacuateAsset::Cw721 {
+        contract: contract.to_string(),
+        token_ids: Some(vec!["owned".to_string(), token_id.to_string()]),
+        start_after: None,
+        limit: None,
+      }).unwrap_err();
+      assert!(matches!(err, ContractError::Std(_)));
+    }
 
-    // Should return an error for invalid address
-    assert!(result.is_err());
+    // Invalid contract addresses are rejected
+    let mut ctx = ExecuteContext { deps: deps.as_mut(), env: mock_env(), info: mock_info("sender", &[]) };
+    let err = evacuate(&mut ctx, EvacuateAsset::Cw721 {
+      contract: "Invalid".to_string(),
+      token_ids: None,
+      start_after: None,
+      limit: None,
+    }).unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
   }
 
   #[test]
-  fn test_change_evacuate_address_success() {
+  fn test_evacuate_all_cw721() {
     let mut deps = mock_dependencies();
     let env = mock_env();
-    let info = mock_info("evacuate_addr", &[]);
     setup_test_state(&mut deps.as_mut());
+    let owner = env.contract.address.to_string();
+
+    // More tokens than fit a single page, which the contract caps to 30
+    let mut tokens: Vec<_> = (0..150).map(|i| (format!("token{:03}", i), owner.clone())).collect();
+    tokens.push(("other".to_string(), "someone_else".to_string()));
+    mock_cw721(&mut deps.querier, "cw721_contract_addr", tokens);
+
+    // One page per evacuation
+    let mut pages = vec![];
+    let mut start_after = None;
+    loop {
+      let mut ctx = ExecuteContext { deps: deps.as_mut(), env: env.clone(), info: mock_info("sender", &[]) };
+      let result = evacuate(&mut ctx, EvacuateAsset::Cw721 {
+        contract: "cw721_contract_addr".to_string(),
+        token_ids: None,
+        start_after: start_after.clone(),
+        limit: None,
+      }).unwrap();
+      let transferred = transferred_nfts(&result);
+      if transferred.is_empty() {
+        break;
+      }
+      start_after = transferred.last().cloned();
+      pages.push(transferred);
+    }
+    assert_eq!(pages.len(), 5);
+    let transferred = pages.concat();
+    assert_eq!(transferred.len(), 150);
+    assert!(!transferred.contains(&"other".to_string()));
 
-    let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info };
-    let new_address = "new_evacuate_addr".to_string();
-
-    let result = change_evacuate_address(&mut ctx, new_address.clone()).unwrap();
+    let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info: mock_info("sender", &[]) };
+    let result = evacuate(&mut ctx, EvacuateAsset::Cw721 {
+      contract: "cw721_contract_addr".to_string(),
+      token_ids: None,
+      start_after: Some("token009".to_string()),
+      limit: Some(2),
+    }).unwrap();
+    assert_eq!(transferred_nfts(&result), vec!["token010", "token011"]);
+  }
+
+  #[test]
+  fn test_evacuate_cw1155_assets() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+
+    let owner = env.contract.address.to_string();
+    let expected_owner = owner.clone();
+    deps.querier.update_wasm(move |query| match query {
+      WasmQuery::Smart { contract_addr, msg } if contract_addr == "cw1155_contract_addr" => {
+        let Cw1155QueryMsg::BatchBalance { owner, token_ids } = cosmwasm_std::from_json(msg).unwrap();
+        assert_eq!(owner, expected_owner);
+        // A misbehaving contract omits balances of unknown tokens
+        let balances = token_ids.iter()
+          .filter(|token_id| *token_id != "unknown")
+          .map(|token_id| Uint128::new(if token_id == "badge" { 0 } else { 5 }))
+          .collect();
+        SystemResult::Ok(cosmwasm_std::ContractResult::Ok(to_json_binary(&BatchBalanceResponse { balances }).unwrap()))
+      }
+      _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: format!("{:?}", query) }),
+    });
+
+    let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info: mock_info("sender", &[]) };
+    let result = evacuate(&mut ctx, EvacuateAsset::Cw1155 {
+      contract: "cw1155_contract_addr".to_string(),
+      token_ids: vec!["reward".to_string(), "badge".to_string(), "ticket".to_string()],
+    }).unwrap();
+
+    // A single batch transfer, skipping tokens without balance
+    assert_eq!(result.messages.len(), 1);
+    match &result.messages[0].msg {
+      CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, funds }) => {
+        assert_eq!(contract_addr, "cw1155_contract_addr");
+        assert!(funds.is_empty());
+        let msg: Cw1155ExecuteMsg = cosmwasm_std::from_json(msg).unwrap();
+        assert_eq!(msg, Cw1155ExecuteMsg::BatchSendFrom {
+          from: owner,
+          to: "evacuate_addr".to_string(),
+          batch: vec![
+            ("reward".to_string(), Uint128::new(5)),
+            ("ticket".to_string(), Uint128::new(5)),
+          ],
+          msg: None,
+        });
+      }
+      _ => panic!("Expected WasmMsg::Execute"),
+    }
+    assert_eq!(result.events[0].attributes[2].value, "cw1155");
+
+    // Balances must match the requested tokens one to one
+    let mut ctx = ExecuteContext { deps: deps.as_mut(), env: mock_env(), info: mock_info("sender", &[]) };
+    let err = evacuate(&mut ctx, EvacuateAsset::Cw1155 {
+      contract: "cw1155_contract_addr".to_string(),
+      token_ids: vec!["unknown".to_string(), "reward".to_string()],
+    }).unwrap_err();
+    assert!(matches!(err, ContractError::Generic(_)));
+  }
+
+  #[test]
+  fn test_evacuate_cw20_invalid_contract() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("sender", &[]);
+    setup_test_state(&mut deps.as_mut());
+
+    let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info };
+    let result = evacuate(&mut ctx, EvacuateAsset::Cw20 {
+      contract: "invalid_address".to_string(),
+      amount: None,
+    });
+
+    // Should return an error for invalid address
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_change_evacuate_address_success() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("evacuate_addr", &[]);
+    setup_test_state(&mut deps.as_mut());
+
+    let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info };
+    let new_address = "new_evacuate_addr".to_string();
+
+    let result = change_evacuate_address(&mut ctx, new_address.clone()).unwrap();
 
     // Should succeed
     assert_eq!(result.messages.len(), 0);
@@ -264,7 +1289,7 @@ mod test {
     // Verify state was updated
     let updated_state = STATE.load(ctx.deps.storage).unwrap();
     assert_eq!(updated_state.evacuate_address, new_address);
-    assert_eq!(updated_state.pool, "pool_token"); // Pool should remain unchanged
+    assert_eq!(updated_state.pool, Token::Native("pool_token".to_string())); // Pool should remain unchanged
   }
 
   #[test]
@@ -300,7 +1325,7 @@ mod test {
 
     // No balance set up, so contract has no assets
     let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info };
-    let result = evacuate(&mut ctx, EvacuateAsset::Native {}).unwrap();
+    let result = evacuate(&mut ctx, EvacuateAsset::Native { denoms: None, exclude: None, amounts: None, start_after: None, limit: None }).unwrap();
 
     // Should succeed but with no messages
     assert_eq!(result.messages.len(), 0);
@@ -323,7 +1348,7 @@ mod test {
     );
 
     let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info };
-    let result = evacuate(&mut ctx, EvacuateAsset::Native {}).unwrap();
+    let result = evacuate(&mut ctx, EvacuateAsset::Native { denoms: None, exclude: None, amounts: None, start_after: None, limit: None }).unwrap();
 
     // Should succeed but with no messages (pool token filtered out)
     assert_eq!(result.messages.len(), 0);
@@ -332,6 +1357,89 @@ mod test {
     assert_eq!(result.attributes[0].value, "evacuate");
   }
 
+  #[test]
+  fn test_evacuate_native_burns_soulp() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("sender", &[]);
+    setup_test_state(&mut deps.as_mut());
+    let denom = format!("factory/{}/SouLP", env.contract.address);
+
+    deps.querier.update_balance(
+      env.contract.address.clone(),
+      vec![coin(100, "uatom"), coin(30, &denom)],
+    );
+
+    let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info };
+    let result = evacuate(&mut ctx, EvacuateAsset::Native { denoms: None, exclude: None, amounts: None, start_after: None, limit: None }).unwrap();
+
+    // SouLP is burned rather than sent to the evacuation address
+    assert_eq!(result.messages.len(), 2);
+    match &result.messages[0].msg {
+      CosmosMsg::Bank(BankMsg::Send { amount, .. }) => assert_eq!(amount, &coins(100, "uatom")),
+      _ => panic!("Expected BankMsg::Send"),
+    }
+    match &result.messages[1].msg {
+      CosmosMsg::Stargate { type_url, value } => {
+        assert_eq!(type_url, MsgBurn::TYPE_URL);
+        assert_eq!(MsgBurn::decode(value.as_slice()).unwrap().amount.unwrap().amount, "30");
+      }
+      _ => panic!("Expected Stargate message"),
+    }
+
+    let event = &result.events[0];
+    let attr = |key: &str| event.attributes.iter().find(|a| a.key == key).unwrap().value.clone();
+    assert_eq!(attr("transfers"), "1");
+    assert_eq!(attr("burned"), "30");
+
+    let totals = TOTALS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(totals.burned.u128(), 30);
+  }
+
+  #[test]
+  fn test_evacuate_native_burns_only_selected_soulp() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    let denom = format!("factory/{}/SouLP", env.contract.address);
+    deps.querier.update_balance(
+      env.contract.address.clone(),
+      vec![coin(100, "uatom"), coin(30, &denom)],
+    );
+
+    let unselected = vec![
+      (Some(vec!["uatom".to_string()]), None, None),
+      (None, Some(vec![denom.clone()]), None),
+      (None, None, Some(denom.clone())),
+    ];
+    for (denoms, exclude, start_after) in unselected {
+      let mut ctx = ExecuteContext { deps: deps.as_mut(), env: env.clone(), info: mock_info("sender", &[]) };
+      let result = evacuate(&mut ctx, EvacuateAsset::Native { denoms, exclude, amounts: None, start_after, limit: None }).unwrap();
+      assert_eq!(result.messages.len(), 1);
+      assert!(matches!(result.messages[0].msg, CosmosMsg::Bank(BankMsg::Send { .. })));
+    }
+    assert!(TOTALS.may_load(deps.as_ref().storage).unwrap().is_none());
+
+    // SouLP sorts first, hence is the only denom of the first page
+    let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info: mock_info("sender", &[]) };
+    let result = evacuate(&mut ctx, EvacuateAsset::Native {
+      denoms: None,
+      exclude: None,
+      amounts: Some(vec![coin(10, &denom)]),
+      start_after: None,
+      limit: Some(1),
+    }).unwrap();
+    assert_eq!(result.messages.len(), 1);
+    match &result.messages[0].msg {
+      CosmosMsg::Stargate { type_url, value } => {
+        assert_eq!(type_url, MsgBurn::TYPE_URL);
+        assert_eq!(MsgBurn::decode(value.as_slice()).unwrap().amount.unwrap().amount, "10");
+      }
+      _ => panic!("Expected Stargate message"),
+    }
+    assert_eq!(TOTALS.load(deps.as_ref().storage).unwrap().burned.u128(), 10);
+  }
+
   #[test]
   fn test_deposit_success() {
     let mut deps = mock_dependencies();
@@ -339,8 +1447,9 @@ mod test {
     let info = mock_info("sender", &coins(100, "pool_token"));
     setup_test_state(&mut deps.as_mut());
 
+    mock_lock(&mut deps, &env, 100);
     let mut ctx = ExecuteContext { deps: deps.as_mut(), env: env.clone(), info };
-    let result = deposit(&mut ctx).unwrap();
+    let result = deposit(&mut ctx, None, None).unwrap();
 
     // Should have 1 message (MsgMint)
     assert_eq!(result.messages.len(), 1);
@@ -372,15 +1481,21 @@ mod test {
 
     // Set up state with a different mint ratio (50%)
     let state = State {
-      pool: "pool_token".to_string(),
+      pool: Token::Native("pool_token".to_string()),
+      pair: "pair".to_string(),
+      asset_infos: vec![],
       evacuate_address: "evacuate_addr".to_string(),
       mint_ratio: Decimal::percent(50),
+      subdenom: "SouLP".to_string(),
+      owner: Some("owner".to_string()),
+      pending_owner: None,
     };
     STATE.save(deps.as_mut().storage, &state).unwrap();
 
     let info = mock_info("sender", &coins(100, "pool_token"));
+    mock_lock(&mut deps, &env, 100);
     let mut ctx = ExecuteContext { deps: deps.as_mut(), env: env.clone(), info };
-    let result = deposit(&mut ctx).unwrap();
+    let result = deposit(&mut ctx, None, None).unwrap();
 
     // Should have 1 message (MsgMint)
     assert_eq!(result.messages.len(), 1);
@@ -412,7 +1527,7 @@ mod test {
     setup_test_state(&mut deps.as_mut());
 
     let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info };
-    let result = deposit(&mut ctx);
+    let result = deposit(&mut ctx, None, None);
 
     // Should return error for no funds
     assert!(result.is_err());
@@ -435,7 +1550,7 @@ mod test {
     setup_test_state(&mut deps.as_mut());
 
     let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info };
-    let result = deposit(&mut ctx);
+    let result = deposit(&mut ctx, None, None);
 
     // Should return error for multiple funds
     assert!(result.is_err());
@@ -455,7 +1570,7 @@ mod test {
     setup_test_state(&mut deps.as_mut());
 
     let mut ctx = ExecuteContext { deps: deps.as_mut(), env, info };
-    let result = deposit(&mut ctx);
+    let result = deposit(&mut ctx, None, None);
 
     // Should return error for wrong asset
     assert!(result.is_err());
@@ -466,4 +1581,903 @@ mod test {
       _ => panic!("Expected InvalidFunds error"),
     }
   }
+
+  #[test]
+  fn test_execute_deposit_returns_mint() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("sender", &coins(100, "pool_token"));
+    setup_test_state(&mut deps.as_mut());
+
+    mock_lock(&mut deps, &env, 100);
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::Deposit { recipient: None, recipients: None }).unwrap();
+
+    // The MsgMint must be passed through to the chain
+    assert_eq!(result.messages.len(), 1);
+
+    assert_eq!(result.events.len(), 1);
+    let event = &result.events[0];
+    assert_eq!(event.ty, "soulp_deposit");
+    let attr = |key: &str| event.attributes.iter().find(|a| a.key == key).unwrap().value.clone();
+    assert_eq!(attr("depositor"), "sender");
+    assert_eq!(attr("recipient"), "sender");
+    assert_eq!(attr("lp_amount"), "100");
+    assert_eq!(attr("minted"), "100");
+  }
+
+  #[test]
+  fn test_execute_evacuate_returns_transfers() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("sender", &[]);
+    setup_test_state(&mut deps.as_mut());
+
+    deps.querier.update_balance(
+      env.contract.address.clone(),
+      vec![coin(100, "uatom"), coin(100, "pool_token")],
+    );
+
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::Evacuate {
+      asset: EvacuateAsset::Native { denoms: None, exclude: None, amounts: None, start_after: None, limit: None },
+    }).unwrap();
+
+    assert_eq!(result.messages.len(), 1);
+    assert_eq!(result.events.len(), 1);
+    assert_eq!(result.events[0].ty, "soulp_evacuate");
+  }
+
+  #[test]
+  fn test_execute_change_evacuate_address_emits_event() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("evacuate_addr", &[]);
+    setup_test_state(&mut deps.as_mut());
+
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::ChangeEvacuateAddress {
+      new_address: "new_evacuate_addr".to_string(),
+    }).unwrap();
+
+    assert_eq!(result.events.len(), 1);
+    let event = &result.events[0];
+    assert_eq!(event.ty, "soulp_change_evacuate_address");
+    assert_eq!(event.attributes[0].value, "evacuate_addr");
+    assert_eq!(event.attributes[1].value, "new_evacuate_addr");
+  }
+
+  #[test]
+  fn test_deposit_updates_totals() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+
+    let info = mock_info("alice", &coins(100, "pool_token"));
+    mock_lock(&mut deps, &env, 100);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Deposit { recipient: None, recipients: None }).unwrap();
+    let info = mock_info("bob", &coins(50, "pool_token"));
+    mock_lock(&mut deps, &env, 50);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Deposit { recipient: None, recipients: None }).unwrap();
+    let info = mock_info("alice", &coins(25, "pool_token"));
+    mock_lock(&mut deps, &env, 25);
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::Deposit { recipient: None, recipients: None }).unwrap();
+
+    let totals = TOTALS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(totals.locked.u128(), 175);
+    assert_eq!(totals.minted.u128(), 175);
+
+    let alice = DEPOSITORS.load(deps.as_ref().storage, &Addr::unchecked("alice")).unwrap();
+    assert_eq!(alice.locked.u128(), 125);
+    assert_eq!(alice.minted.u128(), 125);
+    let bob = DEPOSITORS.load(deps.as_ref().storage, &Addr::unchecked("bob")).unwrap();
+    assert_eq!(bob.locked.u128(), 50);
+
+    let event = &result.events[0];
+    let attr = |key: &str| event.attributes.iter().find(|a| a.key == key).unwrap().value.clone();
+    assert_eq!(attr("total_locked"), "175");
+    assert_eq!(attr("total_minted"), "175");
+  }
+
+  #[test]
+  fn test_transfer_and_accept_ownership() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+
+    // Only the owner may propose a transfer
+    let info = mock_info("new_owner", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::TransferOwnership {
+      new_owner: "new_owner".to_string(),
+    }).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    let info = mock_info("owner", &[]);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::TransferOwnership {
+      new_owner: "new_owner".to_string(),
+    }).unwrap();
+
+    let state = STATE.load(deps.as_ref().storage).unwrap();
+    assert_eq!(state.owner, Some("owner".to_string()));
+    assert_eq!(state.pending_owner, Some("new_owner".to_string()));
+
+    // Only the pending owner may accept
+    let info = mock_info("owner", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::AcceptOwnership {}).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    let info = mock_info("new_owner", &[]);
+    execute(deps.as_mut(), env, info, ExecuteMsg::AcceptOwnership {}).unwrap();
+
+    let state = STATE.load(deps.as_ref().storage).unwrap();
+    assert_eq!(state.owner, Some("new_owner".to_string()));
+    assert_eq!(state.pending_owner, None);
+  }
+
+  #[test]
+  fn test_renounce_ownership() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+
+    let info = mock_info("owner", &[]);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::TransferOwnership {
+      new_owner: "new_owner".to_string(),
+    }).unwrap();
+
+    let info = mock_info("owner", &[]);
+    let result = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::RenounceOwnership {}).unwrap();
+    assert_eq!(result.events[0].ty, "soulp_ownership");
+
+    let state = STATE.load(deps.as_ref().storage).unwrap();
+    assert_eq!(state.owner, None);
+    assert_eq!(state.pending_owner, None);
+
+    // Pending transfers are voided & ownership cannot be regained
+    let info = mock_info("new_owner", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::AcceptOwnership {}).unwrap_err();
+    assert!(matches!(err, ContractError::Renounced {}));
+
+    let info = mock_info("owner", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::TransferOwnership {
+      new_owner: "owner".to_string(),
+    }).unwrap_err();
+    assert!(matches!(err, ContractError::Renounced {}));
+
+    // Configuration changes are disabled
+    let info = mock_info("evacuate_addr", &[]);
+    let err = execute(deps.as_mut(), env, info, ExecuteMsg::ChangeEvacuateAddress {
+      new_address: "new_evacuate_addr".to_string(),
+    }).unwrap_err();
+    assert!(matches!(err, ContractError::Renounced {}));
+  }
+
+  fn mint_of(msg: &SubMsg) -> (String, String) {
+    match msg {
+      SubMsg { msg: CosmosMsg::Stargate { value, .. }, .. } => {
+        let msg_mint = MsgMint::decode(value.as_slice()).unwrap();
+        (msg_mint.mint_to_address, msg_mint.amount.unwrap().amount)
+      }
+      _ => panic!("Expected Stargate message"),
+    }
+  }
+
+  #[test]
+  fn test_deposit_msg_defaults() {
+    let msg: ExecuteMsg = cosmwasm_std::from_json(br#"{"deposit":{}}"#).unwrap();
+    assert_eq!(msg, ExecuteMsg::Deposit { recipient: None, recipients: None });
+  }
+
+  #[test]
+  fn test_deposit_to_recipient() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("treasury", &coins(100, "pool_token"));
+    setup_test_state(&mut deps.as_mut());
+
+    mock_lock(&mut deps, &env, 100);
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::Deposit {
+      recipient: Some("user".to_string()),
+      recipients: None,
+    }).unwrap();
+
+    assert_eq!(result.messages.len(), 1);
+    assert_eq!(mint_of(&result.messages[0]), ("user".to_string(), "100".to_string()));
+
+    let event = &result.events[0];
+    let attr = |key: &str| event.attributes.iter().find(|a| a.key == key).unwrap().value.clone();
+    assert_eq!(attr("depositor"), "treasury");
+    assert_eq!(attr("recipient"), "user");
+
+    // The LP is attributed to the depositor
+    let treasury = DEPOSITORS.load(deps.as_ref().storage, &Addr::unchecked("treasury")).unwrap();
+    assert_eq!(treasury.locked.u128(), 100);
+  }
+
+  #[test]
+  fn test_deposit_split_recipients() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = mock_info("router", &coins(100, "pool_token"));
+    setup_test_state(&mut deps.as_mut());
+
+    mock_lock(&mut deps, &env, 100);
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::Deposit {
+      recipient: None,
+      recipients: Some(vec![
+        WeightedRecipient { address: "alice".to_string(), weight: 1 },
+        WeightedRecipient { address: "bob".to_string(), weight: 1 },
+        WeightedRecipient { address: "dao".to_string(), weight: 1 },
+      ]),
+    }).unwrap();
+
+    // Rounding dust goes to the last recipient
+    assert_eq!(result.messages.len(), 3);
+    assert_eq!(mint_of(&result.messages[0]), ("alice".to_string(), "33".to_string()));
+    assert_eq!(mint_of(&result.messages[1]), ("bob".to_string(), "33".to_string()));
+    assert_eq!(mint_of(&result.messages[2]), ("dao".to_string(), "34".to_string()));
+
+    assert_eq!(result.events.len(), 3);
+    let lp_amounts: Vec<_> = result.events.iter()
+      .map(|event| event.attributes.iter().find(|a| a.key == "lp_amount").unwrap().value.clone())
+      .collect();
+    assert_eq!(lp_amounts, vec!["33", "33", "34"]);
+
+    let totals = TOTALS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(totals.minted.u128(), 100);
+  }
+
+  #[test]
+  fn test_deposit_invalid_recipients() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+
+    let invalid = vec![
+      (Some("user".to_string()), Some(vec![WeightedRecipient { address: "alice".to_string(), weight: 1 }])),
+      (None, Some(vec![])),
+      (None, Some(vec![WeightedRecipient { address: "alice".to_string(), weight: 0 }])),
+    ];
+    for (recipient, recipients) in invalid {
+      let info = mock_info("sender", &coins(100, "pool_token"));
+      let err = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Deposit { recipient, recipients }).unwrap_err();
+      assert!(matches!(err, ContractError::Generic(_)));
+    }
+  }
+
+  #[test]
+  fn test_deposit_carries_remainder() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    STATE.update(deps.as_mut().storage, |state| -> StdResult<_> {
+      Ok(State { mint_ratio: Decimal::percent(30), ..state })
+    }).unwrap();
+
+    // 5 * 0.3 = 1.5, of which 0.5 is carried over
+    let info = mock_info("user", &coins(5, "pool_token"));
+    mock_lock(&mut deps, &env, 5);
+    let result = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Deposit { recipient: None, recipients: None }).unwrap();
+    assert_eq!(mint_of(&result.messages[0]), ("user".to_string(), "1".to_string()));
+    let user = DEPOSITORS.load(deps.as_ref().storage, &Addr::unchecked("user")).unwrap();
+    assert_eq!(user.remainder, Decimal256::percent(50));
+
+    // 5 * 0.3 + 0.5 = 2
+    let info = mock_info("user", &coins(5, "pool_token"));
+    mock_lock(&mut deps, &env, 5);
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::Deposit { recipient: None, recipients: None }).unwrap();
+    assert_eq!(mint_of(&result.messages[0]), ("user".to_string(), "2".to_string()));
+    let user = DEPOSITORS.load(deps.as_ref().storage, &Addr::unchecked("user")).unwrap();
+    assert_eq!(user.minted.u128(), 3);
+    assert_eq!(user.remainder, Decimal256::zero());
+
+    let totals = TOTALS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(totals.minted, totals.max_mintable(Decimal::percent(30)));
+  }
+
+  #[test]
+  fn test_deposit_rejects_zero_mint() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    STATE.update(deps.as_mut().storage, |state| -> StdResult<_> {
+      Ok(State { mint_ratio: Decimal::percent(30), ..state })
+    }).unwrap();
+
+    let info = mock_info("user", &coins(3, "pool_token"));
+    let err = execute(deps.as_mut(), env, info, ExecuteMsg::Deposit { recipient: None, recipients: None }).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidFunds(_)));
+    assert!(TOTALS.may_load(deps.as_ref().storage).unwrap().is_none());
+  }
+
+  #[test]
+  fn test_deposit_rejects_invariant_violation() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    TOTALS.save(deps.as_mut().storage, &Totals {
+      locked: Uint128::new(100),
+      minted: Uint128::new(150),
+      burned: Uint128::zero(),
+      donated: Uint128::zero(),
+    }).unwrap();
+
+    let info = mock_info("user", &coins(10, "pool_token"));
+    let err = execute(deps.as_mut(), env, info, ExecuteMsg::Deposit { recipient: None, recipients: None }).unwrap_err();
+    assert!(matches!(err, ContractError::InvariantViolated(_)));
+  }
+
+  #[test]
+  fn test_deposit_rejects_inconsistent_chain_state() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    let denom = format!("factory/{}/SouLP", env.contract.address);
+    let deposit = ExecuteMsg::Deposit { recipient: None, recipients: None };
+
+    let info = mock_info("user", &coins(100, "pool_token"));
+    mock_lock(&mut deps, &env, 100);
+    execute(deps.as_mut(), env.clone(), info, deposit.clone()).unwrap();
+
+    // The contract lost some of the locked LP
+    mock_lock(&mut deps, &env, 10);
+    deps.querier.update_balance(env.contract.address.clone(), coins(90, "pool_token"));
+    let info = mock_info("user", &coins(10, "pool_token"));
+    let err = execute(deps.as_mut(), env.clone(), info, deposit.clone()).unwrap_err();
+    assert!(matches!(err, ContractError::InvariantViolated(_)));
+
+    // SouLP was minted outside of the recorded totals
+    mock_lock(&mut deps, &env, 10);
+    deps.querier.update_balance("holders", coins(150, &denom));
+    let info = mock_info("user", &coins(10, "pool_token"));
+    let err = execute(deps.as_mut(), env.clone(), info, deposit.clone()).unwrap_err();
+    assert!(matches!(err, ContractError::InvariantViolated(_)));
+
+    // SouLP burned outside of the recorded totals does not block deposits
+    mock_lock(&mut deps, &env, 10);
+    deps.querier.update_balance("holders", coins(60, &denom));
+    let info = mock_info("user", &coins(10, "pool_token"));
+    execute(deps.as_mut(), env.clone(), info, deposit.clone()).unwrap();
+
+    // Consistent state deposits fine
+    mock_lock(&mut deps, &env, 10);
+    let info = mock_info("user", &coins(10, "pool_token"));
+    execute(deps.as_mut(), env, info, deposit).unwrap();
+    assert_eq!(TOTALS.load(deps.as_ref().storage).unwrap().locked.u128(), 120);
+  }
+
+  #[test]
+  fn test_burn() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    let denom = format!("factory/{}/SouLP", env.contract.address);
+
+    let info = mock_info("user", &coins(100, "pool_token"));
+    mock_lock(&mut deps, &env, 100);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Deposit { recipient: None, recipients: None }).unwrap();
+
+    let info = mock_info("user", &coins(40, &denom));
+    let result = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Burn {}).unwrap();
+    assert_eq!(result.messages.len(), 1);
+    match &result.messages[0].msg {
+      CosmosMsg::Stargate { type_url, value } => {
+        assert_eq!(type_url, MsgBurn::TYPE_URL);
+        let msg = MsgBurn::decode(value.as_slice()).unwrap();
+        let amount = msg.amount.unwrap();
+        assert_eq!(amount.denom, denom);
+        assert_eq!(amount.amount, "40");
+        assert_eq!(msg.burn_from_address, env.contract.address.to_string());
+      }
+      _ => panic!("Expected Stargate message"),
+    }
+
+    let event = &result.events[0];
+    assert_eq!(event.ty, "soulp_burn");
+    let attr = |key: &str| event.attributes.iter().find(|a| a.key == key).unwrap().value.clone();
+    assert_eq!(attr("total_burned"), "40");
+    assert_eq!(attr("outstanding"), "60");
+
+    // The locked LP remains, backing fewer SouLP
+    let totals = TOTALS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(totals.locked.u128(), 100);
+    assert_eq!(totals.burned.u128(), 40);
+    assert_eq!(totals.outstanding().u128(), 60);
+  }
+
+  #[test]
+  fn test_burn_invalid_funds() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    let denom = format!("factory/{}/SouLP", env.contract.address);
+
+    let invalid = vec![
+      vec![],
+      coins(100, "pool_token"),
+      vec![coin(100, &denom), coin(100, "uatom")],
+    ];
+    for funds in invalid {
+      let info = mock_info("user", &funds);
+      let err = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Burn {}).unwrap_err();
+      assert!(matches!(err, ContractError::InvalidFunds(_)));
+    }
+  }
+
+  #[test]
+  fn test_donate() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+
+    let info = mock_info("user", &coins(100, "pool_token"));
+    mock_lock(&mut deps, &env, 100);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Deposit { recipient: None, recipients: None }).unwrap();
+
+    let info = mock_info("dao", &coins(50, "pool_token"));
+    let result = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Donate {}).unwrap();
+    assert!(result.messages.is_empty());
+
+    let event = &result.events[0];
+    assert_eq!(event.ty, "soulp_donate");
+    let attr = |key: &str| event.attributes.iter().find(|a| a.key == key).unwrap().value.clone();
+    assert_eq!(attr("donor"), "dao");
+    assert_eq!(attr("total_locked"), "150");
+    assert_eq!(attr("total_donated"), "50");
+
+    let totals = TOTALS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(totals.locked.u128(), 150);
+    assert_eq!(totals.minted.u128(), 100);
+    assert_eq!(totals.donated.u128(), 50);
+
+    let dao = DEPOSITORS.load(deps.as_ref().storage, &Addr::unchecked("dao")).unwrap();
+    assert_eq!(dao.locked.u128(), 0);
+    assert_eq!(dao.donated.u128(), 50);
+
+    let info = mock_info("dao", &coins(50, "uatom"));
+    let err = execute(deps.as_mut(), env, info, ExecuteMsg::Donate {}).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidFunds(_)));
+  }
+
+  #[test]
+  fn test_receive_cw20_donate() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_cw20_state(&mut deps.as_mut());
+
+    let info = mock_info("lp_contract", &[]);
+    let result = execute(deps.as_mut(), env, info, receive_msg("dao", 50, &ReceiveMsg::Donate {})).unwrap();
+    assert!(result.messages.is_empty());
+
+    let totals = TOTALS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(totals.donated.u128(), 50);
+    let dao = DEPOSITORS.load(deps.as_ref().storage, &Addr::unchecked("dao")).unwrap();
+    assert_eq!(dao.donated.u128(), 50);
+  }
+
+  #[test]
+  fn test_reconcile_orphaned_lp() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+
+    // 100 LP deposited, another 50 sent directly
+    let info = mock_info("user", &coins(100, "pool_token"));
+    mock_lock(&mut deps, &env, 100);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Deposit { recipient: None, recipients: None }).unwrap();
+    mock_lock(&mut deps, &env, 50);
+
+    let info = mock_info("owner", &[]);
+    let result = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ReconcileOrphanedLp {
+      recipient: Some("sender".to_string()),
+    }).unwrap();
+    assert_eq!(result.messages.len(), 1);
+    assert_eq!(mint_of(&result.messages[0]), ("sender".to_string(), "50".to_string()));
+
+    let event = result.events.iter().find(|event| event.ty == "soulp_reconcile").unwrap();
+    let attr = |key: &str| event.attributes.iter().find(|a| a.key == key).unwrap().value.clone();
+    assert_eq!(attr("recipient"), "sender");
+    assert_eq!(attr("lp_amount"), "50");
+    assert_eq!(attr("minted"), "50");
+
+    let totals = TOTALS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(totals.locked.u128(), 150);
+    assert_eq!(totals.minted.u128(), 150);
+
+    // Nothing left to reconcile
+    let info = mock_info("owner", &[]);
+    let err = execute(deps.as_mut(), env, info, ExecuteMsg::ReconcileOrphanedLp { recipient: None }).unwrap_err();
+    assert!(matches!(err, ContractError::Generic(_)));
+  }
+
+  #[test]
+  fn test_reconcile_orphaned_lp_as_donation() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    deps.querier.update_balance(env.contract.address.clone(), coins(50, "pool_token"));
+
+    // Only the owner may reconcile
+    let info = mock_info("user", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ReconcileOrphanedLp { recipient: None }).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    let info = mock_info("owner", &[]);
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::ReconcileOrphanedLp { recipient: None }).unwrap();
+    assert!(result.messages.is_empty());
+
+    let totals = TOTALS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(totals.locked.u128(), 50);
+    assert_eq!(totals.minted.u128(), 0);
+    assert_eq!(totals.donated.u128(), 50);
+  }
+
+  fn setup_cw20_state(deps: &mut DepsMut) {
+    setup_test_state(deps);
+    STATE.update(deps.storage, |state| -> StdResult<_> {
+      Ok(State { pool: Token::Cw20("lp_contract".to_string()), ..state })
+    }).unwrap();
+  }
+
+  fn receive_msg(sender: &str, amount: u128, hook: &ReceiveMsg) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+      sender: sender.to_string(),
+      amount: Uint128::new(amount),
+      msg: to_json_binary(hook).unwrap(),
+    })
+  }
+
+  #[test]
+  fn test_receive_cw20_deposit() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_cw20_state(&mut deps.as_mut());
+
+    let hook = ReceiveMsg::Deposit { recipient: None, recipients: None };
+    let info = mock_info("lp_contract", &[]);
+    mock_lock(&mut deps, &env, 100);
+    let result = execute(deps.as_mut(), env, info, receive_msg("user", 100, &hook)).unwrap();
+
+    // Minted to the cw20 sender, not the LP contract
+    assert_eq!(result.messages.len(), 1);
+    assert_eq!(mint_of(&result.messages[0]), ("user".to_string(), "100".to_string()));
+
+    let user = DEPOSITORS.load(deps.as_ref().storage, &Addr::unchecked("user")).unwrap();
+    assert_eq!(user.locked.u128(), 100);
+    let totals = TOTALS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(totals.locked.u128(), 100);
+  }
+
+  #[test]
+  fn test_receive_cw20_deposit_to_recipient() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_cw20_state(&mut deps.as_mut());
+
+    let hook = ReceiveMsg::Deposit { recipient: Some("alice".to_string()), recipients: None };
+    let info = mock_info("lp_contract", &[]);
+    mock_lock(&mut deps, &env, 100);
+    let result = execute(deps.as_mut(), env, info, receive_msg("user", 100, &hook)).unwrap();
+    assert_eq!(mint_of(&result.messages[0]), ("alice".to_string(), "100".to_string()));
+  }
+
+  #[test]
+  fn test_receive_rejects_other_tokens() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let hook = ReceiveMsg::Deposit { recipient: None, recipients: None };
+
+    // Only the configured cw20 LP contract may deposit
+    setup_cw20_state(&mut deps.as_mut());
+    let info = mock_info("other_contract", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, receive_msg("user", 100, &hook)).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidFunds(_)));
+
+    // Native LP pools reject cw20 deposits altogether
+    setup_test_state(&mut deps.as_mut());
+    let info = mock_info("pool_token", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, receive_msg("user", 100, &hook)).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidFunds(_)));
+
+    // Cw20 LP pools reject native deposits
+    setup_cw20_state(&mut deps.as_mut());
+    let info = mock_info("user", &coins(100, "lp_contract"));
+    let err = execute(deps.as_mut(), env, info, ExecuteMsg::Deposit { recipient: None, recipients: None }).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidFunds(_)));
+  }
+
+  /// Mock cw20 `contracts` each holding `balance` for any address. Other contracts fail to answer.
+  fn mock_cw20s(querier: &mut cosmwasm_std::testing::MockQuerier, contracts: &[&str], balance: u128) {
+    let contracts: Vec<String> = contracts.iter().map(|contract| contract.to_string()).collect();
+    querier.update_wasm(move |query| match query {
+      WasmQuery::Smart { contract_addr, .. } if contracts.contains(contract_addr) => SystemResult::Ok(
+        to_json_binary(&cw20::BalanceResponse { balance: Uint128::new(balance) }).into()
+      ),
+      _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "wasm".to_string() }),
+    });
+  }
+
+  #[test]
+  fn test_receive_tracks_other_cw20() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_cw20_state(&mut deps.as_mut());
+    mock_cw20s(&mut deps.querier, &["airdrop_token"], 100);
+
+    for (sender, tracked) in [("airdrop_token", "true"), ("spoofer", "false")] {
+      let info = mock_info(sender, &[]);
+      let result = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "user".to_string(),
+        amount: Uint128::new(100),
+        msg: Binary::default(),
+      })).unwrap();
+      assert!(result.messages.is_empty());
+
+      let event = &result.events[0];
+      assert_eq!(event.ty, "soulp_cw20_tracking");
+      let attr = |key: &str| event.attributes.iter().find(|a| a.key == key).unwrap().value.clone();
+      assert_eq!(attr("action"), "receive");
+      assert_eq!(attr("token"), sender);
+      assert_eq!(attr("tracked"), tracked);
+    }
+    assert!(CW20_TOKENS.has(deps.as_ref().storage, &Addr::unchecked("airdrop_token")));
+    assert!(!CW20_TOKENS.has(deps.as_ref().storage, &Addr::unchecked("lp_contract")));
+    // Senders not answering cw20 queries are not tracked
+    assert!(!CW20_TOKENS.has(deps.as_ref().storage, &Addr::unchecked("spoofer")));
+  }
+
+  #[test]
+  fn test_evacuate_all_cw20() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_cw20_state(&mut deps.as_mut());
+    mock_cw20s(&mut deps.querier, &["lp_contract", "token_a", "token_b", "token_c"], 1000);
+
+    // Only the evacuation address may register tokens, excluding the pool token
+    let info = mock_info("user", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::RegisterCw20 { contract: "token_a".to_string() }).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    let info = mock_info("evacuate_addr", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::RegisterCw20 { contract: "lp_contract".to_string() }).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidFunds(_)));
+
+    for contract in ["token_a", "token_b", "token_c"] {
+      let info = mock_info("evacuate_addr", &[]);
+      let result = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::RegisterCw20 { contract: contract.to_string() }).unwrap();
+      let event = &result.events[0];
+      assert_eq!(event.ty, "soulp_cw20_tracking");
+      assert_eq!(event.attributes[0].value, "register");
+      assert_eq!(event.attributes[1].value, contract);
+    }
+    // The pool token is never evacuated, even if tracked
+    CW20_TOKENS.save(deps.as_mut().storage, &Addr::unchecked("lp_contract"), &()).unwrap();
+    // Tokens failing balance queries are skipped
+    CW20_TOKENS.save(deps.as_mut().storage, &Addr::unchecked("broken_token"), &()).unwrap();
+
+    let evacuate = |deps: &mut OwnedDeps<_, _, _>, start_after: Option<&str>, limit: Option<u32>| -> Vec<String> {
+      let info = mock_info("sender", &[]);
+      let result = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Evacuate {
+        asset: EvacuateAsset::AllCw20 { start_after: start_after.map(|addr| addr.to_string()), limit },
+      }).unwrap();
+      result.messages.iter()
+        .map(|msg| match &msg.msg {
+          CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => contract_addr.clone(),
+          _ => panic!("Expected WasmMsg::Execute"),
+        })
+        .collect()
+    };
+    assert_eq!(evacuate(&mut deps, None, None), vec!["token_a", "token_b", "token_c"]);
+    assert_eq!(evacuate(&mut deps, None, Some(3)), vec!["token_a"]);
+    assert_eq!(evacuate(&mut deps, Some("token_a"), Some(1)), vec!["token_b"]);
+    assert_eq!(evacuate(&mut deps, Some("token_b"), None), vec!["token_c"]);
+
+    let info = mock_info("evacuate_addr", &[]);
+    let result = execute(deps.as_mut(), env, info, ExecuteMsg::UnregisterCw20 { contract: "token_a".to_string() }).unwrap();
+    assert_eq!(result.events[0].ty, "soulp_cw20_tracking");
+    assert_eq!(result.events[0].attributes[0].value, "unregister");
+    assert!(!CW20_TOKENS.has(deps.as_ref().storage, &Addr::unchecked("token_a")));
+  }
+
+  #[test]
+  fn test_evacuate_cw20_refuses_cw20_pool_token() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_cw20_state(&mut deps.as_mut());
+
+    let info = mock_info("sender", &[]);
+    let err = execute(deps.as_mut(), env, info, ExecuteMsg::Evacuate {
+      asset: EvacuateAsset::Cw20 { contract: "lp_contract".to_string(), amount: None },
+    }).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidFunds(_)));
+  }
+
+  fn reply_ok(id: u64) -> Reply {
+    Reply {
+      id,
+      result: SubMsgResult::Ok(SubMsgResponse { events: vec![], data: None }),
+    }
+  }
+
+  #[test]
+  fn test_provide_and_lock() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    // 1 uatom : 4 untrn
+    MockPair::new("pair", "pool_token", PairType::Xyk {}).mock(&mut deps.querier);
+
+    // Contract already holds some locked LP & stray untrn
+    deps.querier.update_balance(env.contract.address.clone(), vec![
+      coin(50, "pool_token"),
+      coin(100, "uatom"),
+      coin(1010, "untrn"),
+    ]);
+
+    let info = mock_info("sender", &[coin(100, "uatom"), coin(1000, "untrn")]);
+    let result = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ProvideAndLock {
+      slippage_tolerance: Some(Decimal::percent(1)),
+      recipient: Some("user".to_string()),
+    }).unwrap();
+
+    // Only the balanced amounts are provided
+    assert_eq!(result.messages.len(), 1);
+    assert_eq!(result.messages[0].id, PROVIDE_AND_LOCK_REPLY_ID);
+    match &result.messages[0].msg {
+      CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, funds }) => {
+        assert_eq!(contract_addr, "pair");
+        assert_eq!(funds, &vec![coin(100, "uatom"), coin(400, "untrn")]);
+        match cosmwasm_std::from_json(msg).unwrap() {
+          PairExecuteMsg::ProvideLiquidity { assets, slippage_tolerance, .. } => {
+            assert_eq!(assets[0].amount.u128(), 100);
+            assert_eq!(assets[1].amount.u128(), 400);
+            assert_eq!(slippage_tolerance, Some(Decimal::percent(1)));
+          }
+          _ => panic!("Expected ProvideLiquidity message"),
+        }
+      }
+      _ => panic!("Expected WasmMsg::Execute"),
+    }
+
+    // The pair consumed the provided assets & issued 200 LP
+    deps.querier.update_balance(env.contract.address.clone(), vec![
+      coin(250, "pool_token"),
+      coin(610, "untrn"),
+    ]);
+    let result = reply(deps.as_mut(), env, reply_ok(PROVIDE_AND_LOCK_REPLY_ID)).unwrap();
+
+    assert_eq!(result.messages.len(), 2);
+    assert_eq!(mint_of(&result.messages[0]), ("user".to_string(), "200".to_string()));
+    match &result.messages[1].msg {
+      CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+        assert_eq!(to_address, "sender");
+        assert_eq!(amount, &vec![coin(600, "untrn")]);
+      }
+      _ => panic!("Expected BankMsg::Send"),
+    }
+
+    let totals = TOTALS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(totals.locked.u128(), 200);
+    assert!(PENDING_LOCK.may_load(deps.as_ref().storage).unwrap().is_none());
+  }
+
+  #[test]
+  fn test_provide_and_lock_invalid_funds() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    MockPair::new("pair", "pool_token", PairType::Xyk {}).mock(&mut deps.querier);
+
+    for funds in [vec![coin(100, "uatom")], vec![coin(100, "uatom"), coin(100, "uosmo")]] {
+      let info = mock_info("sender", &funds);
+      let err = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ProvideAndLock {
+        slippage_tolerance: None,
+        recipient: None,
+      }).unwrap_err();
+      assert!(matches!(err, ContractError::InvalidFunds(_)));
+    }
+  }
+
+  #[test]
+  fn test_balanced_amounts() {
+    let amounts = |deposits: [u128; 2], reserves: [u128; 2]| -> Vec<u128> {
+      balanced_amounts(&deposits.map(Uint128::new), &reserves.map(Uint128::new))
+        .iter().map(|amount| amount.u128()).collect()
+    };
+    assert_eq!(amounts([100, 1000], [1000, 4000]), vec![100, 400]);
+    assert_eq!(amounts([1000, 100], [1000, 4000]), vec![25, 100]);
+    assert_eq!(amounts([100, 400], [1000, 4000]), vec![100, 400]);
+    // Empty pools accept any ratio
+    assert_eq!(amounts([100, 1000], [0, 0]), vec![100, 1000]);
+  }
+
+  #[test]
+  fn test_zap_in() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    // 1 uatom : 4 untrn, 0.3% fee
+    MockPair::new("pair", "pool_token", PairType::Xyk {}).mock(&mut deps.querier);
+
+    let offer_asset = Asset {
+      info: AssetInfo::NativeToken { denom: "uatom".to_string() },
+      amount: Uint128::new(100_000),
+    };
+    deps.querier.update_balance(env.contract.address.clone(), vec![coin(100_000, "uatom")]);
+
+    let info = mock_info("sender", &[coin(100_000, "uatom")]);
+    let result = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ZapIn {
+      offer_asset,
+      max_spread: Some(Decimal::percent(2)),
+    }).unwrap();
+
+    let swap_amount = optimal_swap_amount(Uint128::new(100_000), Uint128::new(1_000_000), 30);
+    assert_eq!(result.messages.len(), 1);
+    assert_eq!(result.messages[0].id, ZAP_IN_REPLY_ID);
+    match &result.messages[0].msg {
+      CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, funds }) => {
+        assert_eq!(contract_addr, "pair");
+        assert_eq!(funds, &vec![coin(swap_amount.u128(), "uatom")]);
+        match cosmwasm_std::from_json(msg).unwrap() {
+          PairExecuteMsg::Swap { offer_asset, ask_asset_info, max_spread, .. } => {
+            assert_eq!(offer_asset.amount, swap_amount);
+            assert_eq!(ask_asset_info, Some(AssetInfo::NativeToken { denom: "untrn".to_string() }));
+            assert_eq!(max_spread, Some(Decimal::percent(2)));
+          }
+          _ => panic!("Expected Swap message"),
+        }
+      }
+      _ => panic!("Expected WasmMsg::Execute"),
+    }
+
+    // The swap returned 150_000 untrn, which is then provided along with the remaining uatom
+    deps.querier.update_balance(env.contract.address.clone(), vec![
+      coin(100_000 - swap_amount.u128(), "uatom"),
+      coin(150_000, "untrn"),
+    ]);
+    let result = reply(deps.as_mut(), env, reply_ok(ZAP_IN_REPLY_ID)).unwrap();
+
+    assert_eq!(result.messages.len(), 1);
+    assert_eq!(result.messages[0].id, PROVIDE_AND_LOCK_REPLY_ID);
+    match &result.messages[0].msg {
+      CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, funds, .. }) => {
+        assert_eq!(contract_addr, "pair");
+        assert_eq!(funds.len(), 2);
+        assert!(funds[0].amount <= Uint128::new(100_000) - swap_amount);
+        assert!(funds[1].amount <= Uint128::new(150_000));
+      }
+      _ => panic!("Expected WasmMsg::Execute"),
+    }
+
+    let pending = PENDING_LOCK.load(deps.as_ref().storage).unwrap();
+    assert_eq!(pending.sender, "sender");
+    assert_eq!(pending.recipient, "sender");
+    assert!(PENDING_ZAP.may_load(deps.as_ref().storage).unwrap().is_none());
+  }
+
+  #[test]
+  fn test_zap_in_invalid_funds() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    setup_test_state(&mut deps.as_mut());
+    MockPair::new("pair", "pool_token", PairType::Xyk {}).mock(&mut deps.querier);
+
+    let offer_asset = Asset {
+      info: AssetInfo::NativeToken { denom: "uatom".to_string() },
+      amount: Uint128::new(100_000),
+    };
+    let info = mock_info("sender", &[coin(50_000, "uatom")]);
+    let err = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ZapIn {
+      offer_asset,
+      max_spread: None,
+    }).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidFunds(_)));
+
+    let offer_asset = Asset {
+      info: AssetInfo::NativeToken { denom: "uosmo".to_string() },
+      amount: Uint128::new(100_000),
+    };
+    let info = mock_info("sender", &[coin(100_000, "uosmo")]);
+    let err = execute(deps.as_mut(), env, info, ExecuteMsg::ZapIn {
+      offer_asset,
+      max_spread: None,
+    }).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidFunds(_)));
+  }
 }
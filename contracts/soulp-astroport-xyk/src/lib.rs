@@ -1,7 +1,10 @@
+pub mod astroport;
 pub mod contract;
+pub mod events;
 pub mod exec;
 pub mod msg;
 pub mod query;
+pub mod reply;
 pub mod state;
 
 pub use r#impl::ContractError;
@@ -1,30 +1,218 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
 use r#impl::msg::EvacuateAsset;
+use r#impl::tokenfactory::TokenMetadata;
 
-use crate::state::State;
+use crate::astroport::Asset;
+use crate::state::{DepositorInfo, State};
 
 #[cw_serde]
 pub struct InstantiateMsg {
-  /// LP token address
-  pub pool: String,
+  /// Astroport XYK pair contract address. The LP token is derived from its pair info.
+  pub pair: String,
   /// Mint ratio from pool token to SouLP. The ratio of LP tokens to assets is more or less
   /// arbitrary, so this is intended to allow bringing it closer to the intended base asset.
   pub mint_ratio: Decimal,
+  /// Subdenom of the SouLP token, i.e. the token will be `factory/{contract}/{subdenom}`
+  pub subdenom: String,
+  /// Metadata of the SouLP token as displayed by wallets & explorers
+  pub metadata: TokenMetadata,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {
+  /// Astroport pair contract issuing the locked LP token. Required when migrating from v0.1,
+  /// which did not record it.
+  pub pair: Option<String>,
+}
+
+#[cw_serde]
+pub struct WeightedRecipient {
+  pub address: String,
+  /// Share of the minted SouLP relative to the sum of all weights
+  pub weight: u64,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
-  /// Permanently lock the provided liquidity & mint a SouLP token.
-  Deposit {},
+  /// Permanently lock the provided liquidity & mint a SouLP token. The SouLP is minted to the
+  /// sender, unless either a `recipient` or weighted `recipients` are given.
+  Deposit {
+    recipient: Option<String>,
+    recipients: Option<Vec<WeightedRecipient>>,
+  },
+  /// Deposit cw20 LP tokens. Expects a [`ReceiveMsg`] hook & is only accepted from the LP token
+  /// contract itself. The SouLP is minted to the cw20 sender, unless recipients are given.
+  Receive(Cw20ReceiveMsg),
+  /// Provide both pool assets sent along as liquidity to the pair, then lock the received LP &
+  /// mint SouLP to the `recipient`, defaulting to the sender. Unused assets are refunded.
+  ProvideAndLock {
+    slippage_tolerance: Option<Decimal>,
+    recipient: Option<String>,
+  },
+  /// Swap the optimal portion of a single pool asset sent along for the other, provide both as
+  /// liquidity, then lock the received LP & mint SouLP to the sender.
+  ZapIn {
+    offer_asset: Asset,
+    max_spread: Option<Decimal>,
+  },
   /// Evacuate assets sent on accident (including LP rewards) to this contract to the configured evacuation address.
   Evacuate {
     asset: EvacuateAsset,
   },
-  /// Change the address to evacuate assets to. Can only be called by the current evacuation address.
+  /// Track a cw20 contract for `EvacuateAsset::AllCw20`. Cw20 contracts sending tokens through
+  /// `Receive` are tracked automatically. Can only be called by the evacuation address.
+  RegisterCw20 {
+    contract: String,
+  },
+  /// Stop tracking a cw20 contract. Can only be called by the evacuation address.
+  UnregisterCw20 {
+    contract: String,
+  },
+  /// Change the address to evacuate assets to. Can only be called by the current evacuation address,
+  /// and only while ownership has not been renounced.
   ChangeEvacuateAddress {
     new_address: String,
   },
+  /// Propose a new owner. Takes effect once accepted by the new owner. Can only be called by the owner.
+  TransferOwnership {
+    new_owner: String,
+  },
+  /// Permanently lock the provided liquidity without minting SouLP, raising the backing of all
+  /// outstanding SouLP.
+  Donate {},
+  /// Lock LP sent to the contract without depositing it, i.e. the pool balance in excess of the
+  /// recorded locked total. Mints the corresponding SouLP to the `recipient`, or treats the LP as a
+  /// donation if none is given. Can only be called by the owner.
+  ReconcileOrphanedLp {
+    recipient: Option<String>,
+  },
+  /// Burn the SouLP sent along, permanently raising the LP backing each outstanding SouLP.
+  Burn {},
+  /// Accept a pending ownership transfer. Can only be called by the pending owner.
+  AcceptOwnership {},
+  /// Irrevocably renounce ownership, disabling all configuration changes. Can only be called by the owner.
+  RenounceOwnership {},
+}
+
+/// Hook messages of cw20 LP tokens sent via [`ExecuteMsg::Receive`].
+#[cw_serde]
+pub enum ReceiveMsg {
+  /// Equivalent to [`ExecuteMsg::Deposit`] for cw20 LP tokens.
+  Deposit {
+    recipient: Option<String>,
+    recipients: Option<Vec<WeightedRecipient>>,
+  },
+  /// Equivalent to [`ExecuteMsg::Donate`] for cw20 LP tokens.
+  Donate {},
+}
+
+#[cw_serde]
+pub struct Cw20Balance {
+  pub contract: String,
+  /// Balance held by this contract, if the contract answers the cw20 balance query. Tokens without
+  /// one are skipped by `EvacuateAsset::AllCw20` & may be unregistered.
+  pub balance: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct OwnershipResponse {
+  pub owner: Option<String>,
+  pub pending_owner: Option<String>,
+  /// Whether ownership has been irrevocably renounced
+  pub renounced: bool,
+}
+
+#[cw_serde]
+pub struct TotalsResponse {
+  /// Total LP tokens locked in this contract
+  pub locked: Uint128,
+  /// Total SouLP minted by this contract
+  pub minted: Uint128,
+  /// Total SouLP burned by this contract
+  pub burned: Uint128,
+  /// Portion of the locked LP donated without minting SouLP
+  pub donated: Uint128,
+  /// SouLP minted & not yet burned
+  pub outstanding: Uint128,
+  /// Locked LP per outstanding SouLP. `None` while no SouLP is outstanding.
+  pub lp_per_token: Option<Decimal>,
+}
+
+#[cw_serde]
+pub struct InvariantResponse {
+  /// Bank supply of SouLP
+  pub supply: Uint128,
+  /// Total SouLP minted by this contract
+  pub minted: Uint128,
+  /// Total SouLP burned by this contract
+  pub burned: Uint128,
+  /// Total LP recorded as locked
+  pub locked: Uint128,
+  /// LP actually held by this contract
+  pub pool_balance: Uint128,
+  /// Most SouLP the locked LP permits minting, i.e. `locked * mint_ratio`
+  pub max_mintable: Uint128,
+  /// Whether the supply does not exceed `minted - burned`, `minted` does not exceed `max_mintable` & the
+  /// contract holds at least the locked LP
+  pub holds: bool,
+}
+
+#[cw_serde]
+pub struct BackingResponse {
+  /// Amount of SouLP
+  pub amount: Uint128,
+  /// Amount of LP backing the given SouLP
+  pub lp_amount: Uint128,
+  /// Underlying pool assets backing the given SouLP
+  pub assets: Vec<Asset>,
+}
+
+#[cw_serde]
+pub struct SimulateDepositResponse {
+  /// SouLP minted for the deposit
+  pub mint_amount: Uint128,
+  /// Fee deducted from the deposit. Deposits are currently free.
+  pub fee_amount: Uint128,
+  /// Total LP locked after the deposit
+  pub total_locked: Uint128,
+  /// Total SouLP minted after the deposit
+  pub total_minted: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulateZapInResponse {
+  /// Amount of the offer asset swapped for the other pool asset
+  pub swap_amount: Uint128,
+  /// Expected return of the swap
+  pub return_amount: Uint128,
+  /// Expected LP received from providing liquidity
+  pub lp_amount: Uint128,
+  /// Expected SouLP minted
+  pub mint_amount: Uint128,
+}
+
+#[cw_serde]
+pub enum TrustStatus {
+  Pass,
+  Warning,
+}
+
+#[cw_serde]
+pub struct TrustItem {
+  /// Identifier of the check, e.g. `contract_admin`
+  pub check: String,
+  pub status: TrustStatus,
+  /// Human readable explanation of the outcome
+  pub detail: String,
+}
+
+#[cw_serde]
+pub struct TrustReport {
+  /// Whether all checks passed, i.e. the liquidity is permanently locked
+  pub permanent: bool,
+  pub items: Vec<TrustItem>,
 }
 
 #[cw_serde]
@@ -37,4 +225,55 @@ pub enum QueryMsg {
   /// Get the SouLP token address.
   #[returns(String)]
   TokenAddress {},
+
+  /// Get the total LP locked & donated, SouLP minted & burned, and the LP backing each outstanding SouLP.
+  #[returns(TotalsResponse)]
+  Totals {},
+
+  /// Check the SouLP supply & the recorded totals against the LP actually held.
+  #[returns(InvariantResponse)]
+  Invariant {},
+
+  /// Get the LP locked & donated, and SouLP minted by the given depositor.
+  #[returns(DepositorInfo)]
+  Depositor {
+    address: String,
+  },
+
+  /// Get the current & pending owner. Once renounced, nobody can change the configuration.
+  #[returns(OwnershipResponse)]
+  Ownership {},
+
+  /// Get the underlying pool assets backing the given amount of SouLP.
+  #[returns(BackingResponse)]
+  Backing {
+    amount: Uint128,
+  },
+
+  /// Simulate depositing the given amount of LP, accounting for the fractional SouLP carried over
+  /// from the `depositor`'s previous deposits, if given.
+  #[returns(SimulateDepositResponse)]
+  SimulateDeposit {
+    amount: Uint128,
+    depositor: Option<String>,
+  },
+
+  /// Simulate zapping in with a single pool asset, accounting for the fractional SouLP carried over
+  /// from the `depositor`'s previous deposits, if given.
+  #[returns(SimulateZapInResponse)]
+  SimulateZapIn {
+    offer_asset: Asset,
+    depositor: Option<String>,
+  },
+
+  /// List the cw20 contracts tracked for evacuation & their balances held by this contract.
+  #[returns(Vec<Cw20Balance>)]
+  Cw20Tokens {
+    start_after: Option<String>,
+    limit: Option<u32>,
+  },
+
+  /// Check whether the locked liquidity is verifiably permanent.
+  #[returns(TrustReport)]
+  TrustReport {},
 }
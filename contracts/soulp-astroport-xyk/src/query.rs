@@ -1,8 +1,13 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_json_binary, Binary, Deps, Env, StdResult};
+use cosmwasm_std::{to_json_binary, Binary, Decimal, Deps, Env, StdError, StdResult, Uint128};
+use r#impl::execute::Token;
+use r#impl::state::tracked_cw20s;
+use r#impl::tokenfactory::{self, TFToken};
 
-use crate::{msg::QueryMsg, state::{State, STATE}};
+use crate::astroport::{Asset, PairQueryMsg, SimulationResponse};
+use crate::exec::{plan_deposit, plan_zap, DepositPlan};
+use crate::{msg::{BackingResponse, Cw20Balance, InvariantResponse, OwnershipResponse, QueryMsg, SimulateDepositResponse, SimulateZapInResponse, TotalsResponse, TrustItem, TrustReport, TrustStatus}, state::{DepositorInfo, State, DEPOSITORS, STATE, TOTALS}};
 
 struct QueryCtx<'a> {
   deps: Deps<'a>,
@@ -15,6 +20,15 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
   match msg {
     QueryMsg::State {} => to_json_binary(&state(ctx)?),
     QueryMsg::TokenAddress {} => to_json_binary(&token_address(ctx)?),
+    QueryMsg::Totals {} => to_json_binary(&totals(ctx)?),
+    QueryMsg::Invariant {} => to_json_binary(&invariant(ctx)?),
+    QueryMsg::Depositor { address } => to_json_binary(&depositor(ctx, address)?),
+    QueryMsg::Ownership {} => to_json_binary(&ownership(ctx)?),
+    QueryMsg::Backing { amount } => to_json_binary(&backing(ctx, amount)?),
+    QueryMsg::SimulateDeposit { amount, depositor } => to_json_binary(&simulate_deposit(ctx, amount, depositor)?),
+    QueryMsg::SimulateZapIn { offer_asset, depositor } => to_json_binary(&simulate_zap_in(ctx, offer_asset, depositor)?),
+    QueryMsg::Cw20Tokens { start_after, limit } => to_json_binary(&cw20_tokens(ctx, start_after, limit)?),
+    QueryMsg::TrustReport {} => to_json_binary(&trust_report(ctx)?),
   }
 }
 
@@ -24,5 +38,558 @@ fn state(ctx: QueryCtx) -> StdResult<State> {
 }
 
 fn token_address(ctx: QueryCtx) -> StdResult<String> {
-  Ok(format!("factory/{}/SouLP", ctx.env.contract.address))
-}
\ No newline at end of file
+  let state = STATE.load(ctx.deps.storage)?;
+  Ok(state.token(ctx.env.contract.address).denom())
+}
+
+fn totals(ctx: QueryCtx) -> StdResult<TotalsResponse> {
+  let totals = TOTALS.may_load(ctx.deps.storage)?.unwrap_or_default();
+  let outstanding = totals.outstanding();
+  let lp_per_token = if outstanding.is_zero() {
+    None
+  } else {
+    Some(Decimal::checked_from_ratio(totals.locked, outstanding)
+      .map_err(|err| StdError::generic_err(err.to_string()))?)
+  };
+  Ok(TotalsResponse {
+    locked: totals.locked,
+    minted: totals.minted,
+    burned: totals.burned,
+    donated: totals.donated,
+    outstanding,
+    lp_per_token,
+  })
+}
+
+fn invariant(ctx: QueryCtx) -> StdResult<InvariantResponse> {
+  let state = STATE.load(ctx.deps.storage)?;
+  let totals = TOTALS.may_load(ctx.deps.storage)?.unwrap_or_default();
+  let contract = ctx.env.contract.address.clone();
+  let supply = state.token(contract.clone()).supply(&ctx.deps.querier)?;
+  let pool_balance = state.pool.query_balance(&ctx.deps.querier, contract)?;
+  let max_mintable = totals.max_mintable(state.mint_ratio);
+  Ok(InvariantResponse {
+    holds: supply <= totals.outstanding() && totals.minted <= max_mintable && pool_balance >= totals.locked,
+    supply,
+    minted: totals.minted,
+    burned: totals.burned,
+    locked: totals.locked,
+    pool_balance,
+    max_mintable,
+  })
+}
+
+fn depositor(ctx: QueryCtx, address: String) -> StdResult<DepositorInfo> {
+  let address = ctx.deps.api.addr_validate(&address)?;
+  Ok(DEPOSITORS.may_load(ctx.deps.storage, &address)?.unwrap_or_default())
+}
+
+fn ownership(ctx: QueryCtx) -> StdResult<OwnershipResponse> {
+  let state = STATE.load(ctx.deps.storage)?;
+  Ok(OwnershipResponse {
+    renounced: state.owner.is_none(),
+    owner: state.owner,
+    pending_owner: state.pending_owner,
+  })
+}
+
+fn backing(ctx: QueryCtx, amount: Uint128) -> StdResult<BackingResponse> {
+  let state = STATE.load(ctx.deps.storage)?;
+  let totals = TOTALS.may_load(ctx.deps.storage)?.unwrap_or_default();
+  let supply = state.token(ctx.env.contract.address.clone()).supply(&ctx.deps.querier)?;
+
+  // Without any SouLP outstanding, report the backing a deposit would receive
+  if supply.is_zero() {
+    let lp_amount = amount.checked_div_floor(state.mint_ratio)
+      .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let assets: Vec<Asset> = ctx.deps.querier.query_wasm_smart(&state.pair, &PairQueryMsg::Share { amount: lp_amount })?;
+    return Ok(BackingResponse { amount, lp_amount, assets });
+  }
+
+  let assets: Vec<Asset> = ctx.deps.querier.query_wasm_smart(&state.pair, &PairQueryMsg::Share { amount: totals.locked })?;
+  Ok(BackingResponse {
+    amount,
+    lp_amount: totals.locked.multiply_ratio(amount, supply),
+    assets: assets.into_iter()
+      .map(|asset| Asset {
+        info: asset.info,
+        amount: asset.amount.multiply_ratio(amount, supply),
+      })
+      .collect(),
+  })
+}
+
+fn simulate_deposit(ctx: QueryCtx, amount: Uint128, depositor: Option<String>) -> StdResult<SimulateDepositResponse> {
+  let state = STATE.load(ctx.deps.storage)?;
+  let plan = simulate_lock(&ctx, &state, amount, depositor)?;
+  Ok(SimulateDepositResponse {
+    mint_amount: plan.mint_amount,
+    fee_amount: Uint128::zero(),
+    total_locked: plan.totals.locked,
+    total_minted: plan.totals.minted,
+  })
+}
+
+fn simulate_zap_in(ctx: QueryCtx, offer_asset: Asset, depositor: Option<String>) -> StdResult<SimulateZapInResponse> {
+  let state = STATE.load(ctx.deps.storage)?;
+  let plan = plan_zap(&ctx.deps.querier, &state, &offer_asset)
+    .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+  let simulation: SimulationResponse = ctx.deps.querier.query_wasm_smart(&state.pair, &PairQueryMsg::Simulation {
+    offer_asset: Asset {
+      info: offer_asset.info,
+      amount: plan.swap_amount,
+    },
+    ask_asset_info: Some(plan.ask_asset_info),
+  })?;
+
+  // Liquidity provided against the reserves after the swap
+  let [offer_reserve, ask_reserve] = plan.reserves;
+  let offer_reserve = offer_reserve + plan.swap_amount;
+  let ask_reserve = ask_reserve.checked_sub(simulation.return_amount)?;
+  let lp_amount = std::cmp::min(
+    (offer_asset.amount - plan.swap_amount).multiply_ratio(plan.total_share, offer_reserve),
+    simulation.return_amount.multiply_ratio(plan.total_share, ask_reserve),
+  );
+
+  let deposit = simulate_lock(&ctx, &state, lp_amount, depositor)?;
+
+  Ok(SimulateZapInResponse {
+    swap_amount: plan.swap_amount,
+    return_amount: simulation.return_amount,
+    lp_amount,
+    mint_amount: deposit.mint_amount,
+  })
+}
+
+/// Plan locking `amount` LP on behalf of the `depositor`, if given, as the deposit would.
+fn simulate_lock(ctx: &QueryCtx, state: &State, amount: Uint128, depositor: Option<String>) -> StdResult<DepositPlan> {
+  let totals = TOTALS.may_load(ctx.deps.storage)?.unwrap_or_default();
+  let info = match depositor {
+    Some(depositor) => {
+      let depositor = ctx.deps.api.addr_validate(&depositor)?;
+      DEPOSITORS.may_load(ctx.deps.storage, &depositor)?.unwrap_or_default()
+    }
+    None => DepositorInfo::default(),
+  };
+  plan_deposit(state, totals, info, amount)
+    .map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+fn cw20_tokens(ctx: QueryCtx, start_after: Option<String>, limit: Option<u32>) -> StdResult<Vec<Cw20Balance>> {
+  let start_after = start_after.map(|addr| ctx.deps.api.addr_validate(&addr)).transpose()?;
+  tracked_cw20s(ctx.deps.storage, start_after.as_ref(), limit)?
+    .into_iter()
+    .map(|contract| {
+      // Still list broken tokens, such that they can be found & unregistered
+      let balance = Token::Cw20(contract.to_string()).query_balance(&ctx.deps.querier, &ctx.env.contract.address).ok();
+      Ok(Cw20Balance { contract: contract.to_string(), balance })
+    })
+    .collect()
+}
+
+fn trust_report(ctx: QueryCtx) -> StdResult<TrustReport> {
+  let state = STATE.load(ctx.deps.storage)?;
+  let totals = TOTALS.may_load(ctx.deps.storage)?.unwrap_or_default();
+  let contract = ctx.env.contract.address.clone();
+  let token = state.token(contract.clone());
+  let mut items = vec![];
+
+  let info = ctx.deps.querier.query_wasm_contract_info(contract.clone())?;
+  items.push(match info.admin {
+    None => trust_item("contract_admin", TrustStatus::Pass, "Contract cannot be migrated".to_string()),
+    Some(admin) => trust_item("contract_admin", TrustStatus::Warning, format!("Contract can be migrated by {}", admin)),
+  });
+
+  items.push(match state.owner {
+    None => trust_item("ownership", TrustStatus::Pass, "Ownership has been renounced".to_string()),
+    Some(owner) => trust_item("ownership", TrustStatus::Warning, format!("Contract is owned by {}", owner)),
+  });
+
+  let denom_admin = tokenfactory::osmosis::query_denom_admin(&ctx.deps.querier, &token.denom())?;
+  items.push(if denom_admin == contract.as_str() {
+    trust_item("denom_admin", TrustStatus::Pass, "SouLP can only be minted by this contract".to_string())
+  } else if denom_admin.is_empty() {
+    trust_item("denom_admin", TrustStatus::Warning, "SouLP has no admin and can no longer be minted".to_string())
+  } else {
+    trust_item("denom_admin", TrustStatus::Warning, format!("SouLP can be minted by {}", denom_admin))
+  });
+
+  let balance = state.pool.query_balance(&ctx.deps.querier, contract)?;
+  items.push(if balance == totals.locked {
+    trust_item("locked_balance", TrustStatus::Pass, format!("Contract holds the {} locked LP", totals.locked))
+  } else {
+    trust_item("locked_balance", TrustStatus::Warning, format!("Contract holds {} LP, but {} are recorded as locked", balance, totals.locked))
+  });
+
+  Ok(TrustReport {
+    permanent: items.iter().all(|item| item.status == TrustStatus::Pass),
+    items,
+  })
+}
+
+fn trust_item(check: &str, status: TrustStatus, detail: String) -> TrustItem {
+  TrustItem {
+    check: check.to_string(),
+    status,
+    detail,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::marker::PhantomData;
+
+  use cosmwasm_std::from_json;
+  use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockStorage};
+  use cosmwasm_std::{coins, Addr, Decimal, Decimal256, Empty, OwnedDeps, SystemError, SystemResult, Uint128, WasmQuery};
+  use prost::Message;
+  use r#impl::state::CW20_TOKENS;
+  use r#impl::tokenfactory::osmosis::{DenomAuthorityMetadata, QueryDenomAuthorityMetadataRequest, QueryDenomAuthorityMetadataResponse};
+  use test_utils::mock_querier::MockQuerier;
+  use crate::astroport::{AssetInfo, PairType};
+  use crate::astroport::testing::MockPair;
+  use crate::exec::execute;
+  use crate::msg::ExecuteMsg;
+  use crate::state::Totals;
+
+  fn denom_admin_response(admin: &str) -> Vec<u8> {
+    QueryDenomAuthorityMetadataResponse {
+      authority_metadata: Some(DenomAuthorityMetadata { admin: admin.to_string() }),
+    }.encode_to_vec()
+  }
+
+  fn setup_trust_deps(querier: MockQuerier, owner: Option<&str>, locked: u128) -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
+    let mut deps = OwnedDeps {
+      custom_query_type: PhantomData::<Empty>,
+      querier,
+      storage: MockStorage::default(),
+      api: MockApi::default(),
+    };
+    STATE.save(deps.as_mut().storage, &State {
+      pool: Token::Native("pool_token".to_string()),
+      pair: "pair".to_string(),
+      asset_infos: vec![],
+      evacuate_address: "evacuate_addr".to_string(),
+      mint_ratio: Decimal::percent(100),
+      subdenom: "SouLP".to_string(),
+      owner: owner.map(|owner| owner.to_string()),
+      pending_owner: None,
+    }).unwrap();
+    TOTALS.save(deps.as_mut().storage, &Totals {
+      locked: Uint128::new(locked),
+      minted: Uint128::new(locked),
+      burned: Uint128::zero(),
+      donated: Uint128::zero(),
+    }).unwrap();
+    deps
+  }
+
+  #[test]
+  fn test_query_totals_and_depositor() {
+    let mut deps = mock_dependencies();
+
+    // Nothing deposited yet
+    let totals: TotalsResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Totals {}).unwrap()).unwrap();
+    assert_eq!(totals.locked, Uint128::zero());
+    assert_eq!(totals.outstanding, Uint128::zero());
+    assert_eq!(totals.lp_per_token, None);
+
+    TOTALS.save(deps.as_mut().storage, &Totals {
+      locked: Uint128::new(100),
+      minted: Uint128::new(50),
+      burned: Uint128::zero(),
+      donated: Uint128::zero(),
+    }).unwrap();
+    DEPOSITORS.save(deps.as_mut().storage, &Addr::unchecked("alice"), &DepositorInfo {
+      locked: Uint128::new(100),
+      minted: Uint128::new(50),
+      donated: Uint128::zero(),
+      remainder: Decimal256::zero(),
+    }).unwrap();
+
+    let totals: TotalsResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Totals {}).unwrap()).unwrap();
+    assert_eq!(totals.locked.u128(), 100);
+    assert_eq!(totals.minted.u128(), 50);
+    assert_eq!(totals.lp_per_token, Some(Decimal::percent(200)));
+
+    let alice: DepositorInfo = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Depositor {
+      address: "alice".to_string(),
+    }).unwrap()).unwrap();
+    assert_eq!(alice.locked.u128(), 100);
+    assert_eq!(alice.minted.u128(), 50);
+
+    let bob: DepositorInfo = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Depositor {
+      address: "bob".to_string(),
+    }).unwrap()).unwrap();
+    assert_eq!(bob, DepositorInfo::default());
+  }
+
+  #[test]
+  fn test_trust_report_permanent() {
+    let env = mock_env();
+    let contract = env.contract.address.as_str();
+    let querier = MockQuerier::new()
+      .with_contract_admin(contract, None)
+      .with_stargate_response(QueryDenomAuthorityMetadataRequest::PATH, denom_admin_response(contract))
+      .with_balance(contract, coins(1000, "pool_token"));
+    let deps = setup_trust_deps(querier, None, 1000);
+
+    let report: TrustReport = from_json(query(deps.as_ref(), env, QueryMsg::TrustReport {}).unwrap()).unwrap();
+    assert!(report.permanent);
+    assert_eq!(report.items.len(), 4);
+    assert!(report.items.iter().all(|item| item.status == TrustStatus::Pass));
+  }
+
+  #[test]
+  fn test_trust_report_warnings() {
+    let env = mock_env();
+    let contract = env.contract.address.as_str();
+    let querier = MockQuerier::new()
+      .with_contract_admin(contract, Some("admin"))
+      .with_stargate_response(QueryDenomAuthorityMetadataRequest::PATH, denom_admin_response("someone_else"))
+      .with_balance(contract, coins(1500, "pool_token"));
+    let deps = setup_trust_deps(querier, Some("owner"), 1000);
+
+    let report: TrustReport = from_json(query(deps.as_ref(), env, QueryMsg::TrustReport {}).unwrap()).unwrap();
+    assert!(!report.permanent);
+    let checks: Vec<_> = report.items.iter()
+      .filter(|item| item.status == TrustStatus::Warning)
+      .map(|item| item.check.as_str())
+      .collect();
+    assert_eq!(checks, vec!["contract_admin", "ownership", "denom_admin", "locked_balance"]);
+  }
+
+  #[test]
+  fn test_query_invariant() {
+    let env = mock_env();
+    let contract = env.contract.address.as_str();
+    let denom = format!("factory/{}/SouLP", contract);
+    let querier = MockQuerier::new()
+      .with_balance(contract, coins(1000, "pool_token"))
+      .with_balance("alice", coins(1000, &denom));
+    let mut deps = setup_trust_deps(querier, None, 1000);
+
+    let invariant: InvariantResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Invariant {}).unwrap()).unwrap();
+    assert!(invariant.holds);
+    assert_eq!(invariant.supply.u128(), 1000);
+    assert_eq!(invariant.pool_balance.u128(), 1000);
+    assert_eq!(invariant.max_mintable.u128(), 1000);
+
+    // More minted than the locked LP permits
+    TOTALS.save(deps.as_mut().storage, &Totals {
+      locked: Uint128::new(1000),
+      minted: Uint128::new(1200),
+      burned: Uint128::new(200),
+      donated: Uint128::zero(),
+    }).unwrap();
+    let invariant: InvariantResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Invariant {}).unwrap()).unwrap();
+    assert!(!invariant.holds);
+
+    // Less LP held than recorded as locked
+    TOTALS.save(deps.as_mut().storage, &Totals {
+      locked: Uint128::new(1500),
+      minted: Uint128::new(1000),
+      burned: Uint128::zero(),
+      donated: Uint128::zero(),
+    }).unwrap();
+    let invariant: InvariantResponse = from_json(query(deps.as_ref(), env, QueryMsg::Invariant {}).unwrap()).unwrap();
+    assert!(!invariant.holds);
+  }
+
+  #[test]
+  fn test_query_cw20_tokens() {
+    let env = mock_env();
+    let querier = MockQuerier::new().with_cw20_balance(env.contract.address.as_str(), 500);
+    let mut deps = setup_trust_deps(querier, None, 0);
+    for contract in ["token_a", "token_b", "token_c"] {
+      CW20_TOKENS.save(deps.as_mut().storage, &Addr::unchecked(contract), &()).unwrap();
+    }
+
+    let tokens: Vec<Cw20Balance> = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Cw20Tokens {
+      start_after: None,
+      limit: Some(2),
+    }).unwrap()).unwrap();
+    assert_eq!(tokens, vec![
+      Cw20Balance { contract: "token_a".to_string(), balance: Some(Uint128::new(500)) },
+      Cw20Balance { contract: "token_b".to_string(), balance: Some(Uint128::new(500)) },
+    ]);
+
+    let tokens: Vec<Cw20Balance> = from_json(query(deps.as_ref(), env, QueryMsg::Cw20Tokens {
+      start_after: Some("token_b".to_string()),
+      limit: None,
+    }).unwrap()).unwrap();
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].contract, "token_c");
+  }
+
+  #[test]
+  fn test_query_cw20_tokens_lists_broken_tokens() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    for contract in ["broken_token", "token_a"] {
+      CW20_TOKENS.save(deps.as_mut().storage, &Addr::unchecked(contract), &()).unwrap();
+    }
+    deps.querier.update_wasm(|query| match query {
+      WasmQuery::Smart { contract_addr, .. } if contract_addr == "token_a" => SystemResult::Ok(
+        to_json_binary(&cw20::BalanceResponse { balance: Uint128::new(500) }).into()
+      ),
+      _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "wasm".to_string() }),
+    });
+
+    let tokens: Vec<Cw20Balance> = from_json(query(deps.as_ref(), env, QueryMsg::Cw20Tokens {
+      start_after: None,
+      limit: None,
+    }).unwrap()).unwrap();
+    assert_eq!(tokens, vec![
+      Cw20Balance { contract: "broken_token".to_string(), balance: None },
+      Cw20Balance { contract: "token_a".to_string(), balance: Some(Uint128::new(500)) },
+    ]);
+  }
+
+  #[test]
+  fn test_query_token_address() {
+    let deps = setup_trust_deps(MockQuerier::new(), None, 0);
+    let env = mock_env();
+    let denom: String = from_json(query(deps.as_ref(), env.clone(), QueryMsg::TokenAddress {}).unwrap()).unwrap();
+    assert_eq!(denom, format!("factory/{}/SouLP", env.contract.address));
+  }
+
+  #[test]
+  fn test_query_backing() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    // 1 LP = 0.5 uatom + 2 untrn
+    MockPair::new("pair", "pool_token", PairType::Xyk {}).mock(&mut deps.querier);
+    STATE.save(deps.as_mut().storage, &State {
+      pool: Token::Native("pool_token".to_string()),
+      pair: "pair".to_string(),
+      asset_infos: vec![],
+      evacuate_address: "evacuate_addr".to_string(),
+      mint_ratio: Decimal::percent(50),
+      subdenom: "SouLP".to_string(),
+      owner: None,
+      pending_owner: None,
+    }).unwrap();
+
+    // Nothing outstanding yet: 100 SouLP would be minted for 200 LP
+    let backing: BackingResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Backing {
+      amount: Uint128::new(100),
+    }).unwrap()).unwrap();
+    assert_eq!(backing.lp_amount.u128(), 200);
+    assert_eq!(backing.assets[0].amount.u128(), 100);
+    assert_eq!(backing.assets[1].amount.u128(), 400);
+
+    // 1000 LP locked for 400 SouLP outstanding
+    TOTALS.save(deps.as_mut().storage, &Totals {
+      locked: Uint128::new(1000),
+      minted: Uint128::new(400),
+      burned: Uint128::zero(),
+      donated: Uint128::zero(),
+    }).unwrap();
+    let denom = format!("factory/{}/SouLP", env.contract.address);
+    deps.querier.update_balance("alice", coins(400, &denom));
+
+    let backing: BackingResponse = from_json(query(deps.as_ref(), env, QueryMsg::Backing {
+      amount: Uint128::new(100),
+    }).unwrap()).unwrap();
+    assert_eq!(backing.amount.u128(), 100);
+    assert_eq!(backing.lp_amount.u128(), 250);
+    assert_eq!(backing.assets[0].amount.u128(), 125);
+    assert_eq!(backing.assets[1].amount.u128(), 500);
+  }
+
+  #[test]
+  fn test_simulate_deposit_matches_deposit() {
+    let env = mock_env();
+    let querier = MockQuerier::new().with_balance(env.contract.address.as_str(), coins(1001, "pool_token"));
+    let mut deps = setup_trust_deps(querier, None, 1000);
+    STATE.update(deps.as_mut().storage, |state| -> StdResult<_> {
+      Ok(State { mint_ratio: Decimal::percent(33), ..state })
+    }).unwrap();
+    TOTALS.save(deps.as_mut().storage, &Totals::default()).unwrap();
+
+    let simulation: SimulateDepositResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::SimulateDeposit {
+      amount: Uint128::new(1001),
+      depositor: None,
+    }).unwrap()).unwrap();
+    assert_eq!(simulation.mint_amount.u128(), 330);
+    assert_eq!(simulation.fee_amount.u128(), 0);
+
+    let info = mock_info("user", &coins(1001, "pool_token"));
+    execute(deps.as_mut(), env, info, ExecuteMsg::Deposit { recipient: None, recipients: None }).unwrap();
+    let totals = TOTALS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(totals.minted, simulation.mint_amount);
+    assert_eq!(totals.minted, simulation.total_minted);
+    assert_eq!(totals.locked, simulation.total_locked);
+  }
+
+  #[test]
+  fn test_simulate_zap_in() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    MockPair::new("pair", "pool_token", PairType::Xyk {}).mock(&mut deps.querier);
+    STATE.save(deps.as_mut().storage, &State {
+      pool: Token::Native("pool_token".to_string()),
+      pair: "pair".to_string(),
+      asset_infos: vec![
+        AssetInfo::NativeToken { denom: "uatom".to_string() },
+        AssetInfo::NativeToken { denom: "untrn".to_string() },
+      ],
+      evacuate_address: "evacuate_addr".to_string(),
+      mint_ratio: Decimal::percent(100),
+      subdenom: "SouLP".to_string(),
+      owner: None,
+      pending_owner: None,
+    }).unwrap();
+
+    let offer_asset = Asset {
+      info: AssetInfo::NativeToken { denom: "uatom".to_string() },
+      amount: Uint128::new(100_000),
+    };
+    let simulation: SimulateZapInResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::SimulateZapIn {
+      offer_asset: offer_asset.clone(),
+      depositor: None,
+    }).unwrap()).unwrap();
+
+    // Roughly half is swapped, slightly less due to the price impact
+    assert!(simulation.swap_amount > Uint128::new(48_000) && simulation.swap_amount < Uint128::new(50_000));
+    // The pool is worth 2_000_000 uatom for 2_000_000 LP, less the fee & price impact
+    assert!(simulation.lp_amount > Uint128::new(95_000) && simulation.lp_amount < Uint128::new(100_000));
+    assert_eq!(simulation.mint_amount, simulation.lp_amount);
+
+    // The depositor's carried remainder is minted along, just like a deposit
+    STATE.update(deps.as_mut().storage, |state| -> StdResult<_> {
+      Ok(State { mint_ratio: Decimal::percent(30), ..state })
+    }).unwrap();
+    // 13 LP previously deposited at 30% minted 3 SouLP, carrying 0.9 over
+    TOTALS.save(deps.as_mut().storage, &Totals {
+      locked: Uint128::new(13),
+      minted: Uint128::new(3),
+      burned: Uint128::zero(),
+      donated: Uint128::zero(),
+    }).unwrap();
+    DEPOSITORS.save(deps.as_mut().storage, &Addr::unchecked("user"), &DepositorInfo {
+      locked: Uint128::new(13),
+      minted: Uint128::new(3),
+      donated: Uint128::zero(),
+      remainder: Decimal256::percent(90),
+    }).unwrap();
+    let simulate = |depositor: Option<&str>| -> SimulateZapInResponse {
+      from_json(query(deps.as_ref(), env.clone(), QueryMsg::SimulateZapIn {
+        offer_asset: offer_asset.clone(),
+        depositor: depositor.map(|depositor| depositor.to_string()),
+      }).unwrap()).unwrap()
+    };
+    let anonymous = simulate(None);
+    let user = simulate(Some("user"));
+    assert_eq!(anonymous.lp_amount, user.lp_amount);
+    assert_eq!(user.mint_amount, anonymous.mint_amount + Uint128::new(1));
+    let deposit: SimulateDepositResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::SimulateDeposit {
+      amount: user.lp_amount,
+      depositor: Some("user".to_string()),
+    }).unwrap()).unwrap();
+    assert_eq!(user.mint_amount, deposit.mint_amount);
+  }
+}
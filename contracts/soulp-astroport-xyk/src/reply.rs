@@ -0,0 +1,69 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, Reply, Response};
+
+use crate::exec::{lock, provide};
+use crate::state::{PENDING_LOCK, PENDING_ZAP, STATE};
+use crate::{ContractError, ContractResult};
+
+pub const PROVIDE_AND_LOCK_REPLY_ID: u64 = 1;
+pub const ZAP_IN_REPLY_ID: u64 = 2;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> ContractResult<Response> {
+  match msg.id {
+    PROVIDE_AND_LOCK_REPLY_ID => provide_and_lock(deps, env),
+    ZAP_IN_REPLY_ID => zap_in(deps, env),
+    id => Err(ContractError::Generic(format!("Unknown reply id: {}", id))),
+  }
+}
+
+/// Lock the LP received from providing liquidity & refund unused pool assets.
+fn provide_and_lock(mut deps: DepsMut, env: Env) -> ContractResult<Response> {
+  let pending = PENDING_LOCK.load(deps.storage)?;
+  PENDING_LOCK.remove(deps.storage);
+
+  let state = STATE.load(deps.storage)?;
+  let contract = env.contract.address.clone();
+  let lp_balance = state.pool.query_balance(&deps.querier, &contract)?;
+  let received = lp_balance.checked_sub(pending.lp_balance)?;
+  if received.is_zero() {
+    return Err(ContractError::Generic("No LP received from the pair".to_string()));
+  }
+
+  let mut refund: Vec<Coin> = vec![];
+  for balance in pending.balances {
+    let current = deps.querier.query_balance(&contract, &balance.denom)?;
+    let unused = current.amount.checked_sub(balance.amount)?;
+    if !unused.is_zero() {
+      refund.push(Coin { denom: balance.denom, amount: unused });
+    }
+  }
+
+  let mut response = lock(deps.branch(), &env, &state, &pending.sender, received, vec![(pending.recipient, 1)])?;
+  if !refund.is_empty() {
+    response = response.add_message(BankMsg::Send {
+      to_address: pending.sender.to_string(),
+      amount: refund,
+    });
+  }
+  Ok(response)
+}
+
+/// Provide the remaining offer asset & the swap's return as liquidity.
+fn zap_in(mut deps: DepsMut, env: Env) -> ContractResult<Response> {
+  let pending = PENDING_ZAP.load(deps.storage)?;
+  PENDING_ZAP.remove(deps.storage);
+
+  let state = STATE.load(deps.storage)?;
+  let submsg = provide(
+    deps.branch(),
+    &env,
+    &state,
+    pending.sender.clone(),
+    pending.sender,
+    pending.balances,
+    pending.max_spread,
+  )?;
+  Ok(Response::new().add_submessage(submsg))
+}
@@ -1,13 +1,110 @@
-use cosmwasm_std::Decimal;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, Uint128};
+use cw_storage_plus::{Item, Map};
+use r#impl::execute::Token;
+use r#impl::tokenfactory;
+
+use crate::astroport::AssetInfo;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
+  /// LP token of the pair, either a native denom or a cw20 contract
+  pub pool: Token,
+  /// Astroport XYK pair contract issuing the LP token
+  pub pair: String,
+  /// Assets of the pair
+  pub asset_infos: Vec<AssetInfo>,
+  pub evacuate_address: String,
+  pub mint_ratio: Decimal,
+  /// Subdenom of the SouLP tokenfactory denom
+  pub subdenom: String,
+  /// Owner permitted to change the configuration. `None` once ownership has been renounced.
+  pub owner: Option<String>,
+  /// Proposed new owner, pending acceptance.
+  pub pending_owner: Option<String>,
+}
+
+impl State {
+  /// The SouLP token, owned by the given contract address
+  pub fn token(&self, contract: Addr) -> tokenfactory::osmosis::TFToken {
+    tokenfactory::osmosis::TFToken::new(contract, self.subdenom.clone())
+  }
+}
+
+/// Layout of [`State`] prior to v0.2.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StateV0_1 {
   pub pool: String,
   pub evacuate_address: String,
   pub mint_ratio: Decimal,
 }
 
+/// Running totals across all deposits.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Totals {
+  /// Total LP tokens locked in this contract
+  pub locked: Uint128,
+  /// Total SouLP minted by this contract
+  pub minted: Uint128,
+  /// Total SouLP burned by this contract
+  pub burned: Uint128,
+  /// Portion of the locked LP donated, i.e. locked without minting SouLP
+  pub donated: Uint128,
+}
+
+impl Totals {
+  /// SouLP minted & not yet burned
+  pub fn outstanding(&self) -> Uint128 {
+    self.minted.saturating_sub(self.burned)
+  }
+
+  /// Most SouLP the locked LP permits minting
+  pub fn max_mintable(&self, mint_ratio: Decimal) -> Uint128 {
+    self.locked.mul_floor(mint_ratio)
+  }
+}
+
+/// Running totals of a single depositor.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct DepositorInfo {
+  /// LP tokens locked by this depositor
+  pub locked: Uint128,
+  /// SouLP minted to this depositor
+  pub minted: Uint128,
+  /// LP tokens donated by this depositor, in addition to `locked`
+  pub donated: Uint128,
+  /// Fractional SouLP owed to this depositor, carried over to their next deposit
+  pub remainder: Decimal256,
+}
+
+/// Liquidity provision in flight, awaiting its reply to lock the received LP.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingLock {
+  /// Sender providing the pool assets, who is refunded any unused assets
+  pub sender: Addr,
+  /// Recipient of the minted SouLP
+  pub recipient: Addr,
+  /// LP balance of the contract prior to providing liquidity
+  pub lp_balance: Uint128,
+  /// Balances of the pool assets held by the contract prior to this transaction
+  pub balances: Vec<Coin>,
+}
+
+/// Swap of a single asset in flight, awaiting its reply to provide liquidity.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingZap {
+  /// Sender offering the asset, who receives the SouLP
+  pub sender: Addr,
+  /// Balances of the pool assets held by the contract prior to this transaction
+  pub balances: Vec<Coin>,
+  /// Maximum spread of the swap, also used as slippage tolerance when providing liquidity
+  pub max_spread: Option<Decimal>,
+}
+
 pub const STATE: Item<State> = Item::new("state");
+pub const STATE_V0_1: Item<StateV0_1> = Item::new("state");
+pub const TOTALS: Item<Totals> = Item::new("totals");
+pub const DEPOSITORS: Map<&Addr, DepositorInfo> = Map::new("depositors");
+pub const PENDING_LOCK: Item<PendingLock> = Item::new("pending_lock");
+pub const PENDING_ZAP: Item<PendingZap> = Item::new("pending_zap");
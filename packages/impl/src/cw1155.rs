@@ -0,0 +1,28 @@
+//! Minimal subset of the cw1155 multi-token interface used for evacuation.
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Binary, Uint128};
+
+#[cw_serde]
+pub enum Cw1155ExecuteMsg {
+  /// Transfer a batch of tokens from `from` to `to`
+  BatchSendFrom {
+    from: String,
+    to: String,
+    batch: Vec<(String, Uint128)>,
+    msg: Option<Binary>,
+  },
+}
+
+#[cw_serde]
+pub enum Cw1155QueryMsg {
+  /// Balances of the given tokens held by `owner`, in the same order
+  BatchBalance {
+    owner: String,
+    token_ids: Vec<String>,
+  },
+}
+
+#[cw_serde]
+pub struct BatchBalanceResponse {
+  pub balances: Vec<Uint128>,
+}
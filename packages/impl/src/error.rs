@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,12 +6,36 @@ pub enum ContractError {
   #[error("{0}")]
   Std(#[from] StdError),
 
+  #[error("{0}")]
+  Overflow(#[from] OverflowError),
+
   #[error("Unauthorized")]
   Unauthorized {},
 
+  #[error("Ownership has been renounced")]
+  Renounced {},
+
   #[error("Invalid funds: {0}")]
   InvalidFunds(String),
 
+  #[error("Cannot migrate from contract {actual} to {expected}")]
+  ContractMismatch {
+    expected: String,
+    actual: String,
+  },
+
+  #[error("Cannot downgrade from {from} to {to}")]
+  Downgrade {
+    from: String,
+    to: String,
+  },
+
+  #[error("Invalid version: {0}")]
+  SemVer(String),
+
+  #[error("Invariant violated: {0}")]
+  InvariantViolated(String),
+
   #[error("{0}")]
   Generic(String),
 }
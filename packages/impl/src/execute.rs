@@ -1,9 +1,14 @@
-use cosmwasm_std::{to_json_binary, Addr, BankMsg, CosmosMsg, DepsMut, Env, MessageInfo, WasmMsg};
-use cw20::{Cw20Contract, Cw20ExecuteMsg};
-use cw721::Cw721ExecuteMsg;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_binary, Addr, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Order, QuerierWrapper, StdResult, Uint128, WasmMsg};
+use cw20::{BalanceResponse, Cw20Contract, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw721::{Cw721ExecuteMsg, Cw721QueryMsg, OwnerOfResponse, TokensResponse};
+use cw_storage_plus::Bound;
 
+use crate::cw1155::{BatchBalanceResponse, Cw1155ExecuteMsg, Cw1155QueryMsg};
 use crate::error::ContractError;
 use crate::msg::EvacuateAsset;
+use crate::state::{CW20_TOKENS, MAX_LIMIT};
+use crate::tokenfactory::TFToken;
 
 pub struct ExecuteContext<'a> {
   pub deps: DepsMut<'a>,
@@ -11,6 +16,7 @@ pub struct ExecuteContext<'a> {
   pub info: MessageInfo,
 }
 
+#[cw_serde]
 pub enum Token {
   /// Native token address
   Native(String),
@@ -18,46 +24,124 @@ pub enum Token {
   Cw20(String),
 }
 
+impl Token {
+  /// Balance of this token held by `address`
+  pub fn query_balance(&self, querier: &QuerierWrapper, address: impl Into<String>) -> StdResult<Uint128> {
+    match self {
+      Token::Native(denom) => Ok(querier.query_balance(address, denom)?.amount),
+      Token::Cw20(contract) => {
+        let res: BalanceResponse = querier.query_wasm_smart(contract, &Cw20QueryMsg::Balance {
+          address: address.into(),
+        })?;
+        Ok(res.balance)
+      }
+    }
+  }
+}
+
+/// Messages of an evacuation
+pub struct Evacuation {
+  /// Transfers to the recipient
+  pub transfers: Vec<CosmosMsg>,
+  /// Burn of the contract's own token, which is retired rather than evacuated
+  pub burn: Vec<CosmosMsg>,
+  /// Amount of the contract's own token burned
+  pub burned: Uint128,
+}
+
+impl Evacuation {
+  fn transfers(transfers: Vec<CosmosMsg>) -> Self {
+    Self { transfers, burn: vec![], burned: Uint128::zero() }
+  }
+}
+
+/// Evacuate the requested assets to the `recipient`. The `lp_token` is never evacuated, and the
+/// contract's `own_token` is burned instead.
 pub fn evacuate(
   ctx: &ExecuteContext,
   lp_token: Token,
+  own_token: &impl TFToken,
   request: EvacuateAsset,
   recipient: Addr,
-) -> Result<Vec<CosmosMsg>, ContractError> {
+) -> Result<Evacuation, ContractError> {
   match request {
-    EvacuateAsset::Native {} => {
+    EvacuateAsset::Native { denoms, exclude, amounts, start_after, limit } => {
       let mut messages: Vec<CosmosMsg> = vec![];
       let balances = ctx.deps.querier
         .query_all_balances(ctx.env.contract.address.clone())?;
 
+      let own_denom = own_token.denom();
       let lp_token = if let Token::Native(lp_token) = lp_token {
         Some(lp_token)
       } else {
         None
       };
 
-      let balances = balances
-        .iter()
+      let mut balances: Vec<Coin> = balances
+        .into_iter()
         .filter(|balance| {
           if let Some(lp_token) = &lp_token {
             &balance.denom != lp_token
           } else {
             true
           }
-        });
-      for balance in balances {
+        })
+        .collect();
+
+      if let Some(denoms) = denoms {
+        if let Some(denom) = denoms.iter().find(|denom| !balances.iter().any(|balance| &balance.denom == *denom)) {
+          return Err(ContractError::InvalidFunds(format!("No evacuable balance of {}", denom)));
+        }
+        balances.retain(|balance| denoms.contains(&balance.denom));
+      }
+      if let Some(exclude) = exclude {
+        balances.retain(|balance| !exclude.contains(&balance.denom));
+      }
+      if let Some(amounts) = amounts {
+        for (i, amount) in amounts.iter().enumerate() {
+          if amounts[..i].iter().any(|other| other.denom == amount.denom) {
+            return Err(ContractError::InvalidFunds(format!("Duplicate amount of {}", amount.denom)));
+          }
+          let balance = balances.iter_mut()
+            .find(|balance| balance.denom == amount.denom)
+            .ok_or_else(|| ContractError::InvalidFunds(format!("No evacuable balance of {}", amount.denom)))?;
+          if amount.amount.is_zero() || amount.amount > balance.amount {
+            return Err(ContractError::InvalidFunds(format!("Cannot evacuate {} of {} held", amount, balance)));
+          }
+          balance.amount = amount.amount;
+        }
+      }
+
+      // Paginate over denoms to bound the size of the transfer
+      balances.sort_by(|a, b| a.denom.cmp(&b.denom));
+      let balances: Vec<Coin> = balances
+        .into_iter()
+        .filter(|balance| start_after.as_ref().is_none_or(|start_after| &balance.denom > start_after))
+        .take(limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize)
+        .collect();
+
+      // The contract's own token is only burned if selected, just like any other denom
+      let (own, balances): (Vec<Coin>, Vec<Coin>) = balances
+        .into_iter()
+        .partition(|balance| balance.denom == own_denom);
+      let burned = own.first().map(|balance| balance.amount).unwrap_or_default();
+
+      if !balances.is_empty() {
         messages.push(BankMsg::Send {
           to_address: recipient.to_string(),
-          amount: vec![balance.clone()],
+          amount: balances,
         }.into());
       }
-      return Ok(messages)
+      Ok(Evacuation {
+        transfers: messages,
+        burn: if burned.is_zero() { vec![] } else { own_token.burn(burned) },
+        burned,
+      })
     }
-    EvacuateAsset::Cw20 { contract } => {
+    EvacuateAsset::Cw20 { contract, amount } => {
       let mut messages: Vec<CosmosMsg> = vec![];
       let contract = ctx.deps.api.addr_validate(&contract)?;
       let contract = Cw20Contract(contract);
-      let balance = Cw20Contract::balance(&contract, &ctx.deps.querier, ctx.env.contract.address.clone())?;
 
       if let Token::Cw20(lp_token) = lp_token {
         if lp_token == contract.addr() {
@@ -65,29 +149,138 @@ pub fn evacuate(
         }
       }
 
+      let balance = Cw20Contract::balance(&contract, &ctx.deps.querier, ctx.env.contract.address.clone())?;
+      let amount = match amount {
+        Some(amount) if amount.is_zero() || amount > balance =>
+          return Err(ContractError::InvalidFunds(format!("Cannot evacuate {} of {} held", amount, balance))),
+        Some(amount) => amount,
+        None => balance,
+      };
+
       messages.push(WasmMsg::Execute {
         contract_addr: contract.addr().to_string(),
         msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
           recipient: recipient.to_string(),
-          amount: balance,
+          amount,
         })?,
         funds: vec![],
       }.into());
-      return Ok(messages)
+      Ok(Evacuation::transfers(messages))
+    }
+    EvacuateAsset::AllCw20 { start_after, limit } => {
+      let mut messages: Vec<CosmosMsg> = vec![];
+      let start_after = start_after.map(|addr| ctx.deps.api.addr_validate(&addr)).transpose()?;
+      let contracts = CW20_TOKENS
+        .keys(&*ctx.deps.storage, start_after.as_ref().map(Bound::exclusive), None, Order::Ascending)
+        .take(limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize)
+        .collect::<StdResult<Vec<_>>>()?;
+      for contract in contracts {
+        if lp_token == Token::Cw20(contract.to_string()) {
+          continue;
+        }
+        // A broken tracked token must not block evacuating the others
+        let Ok(balance) = Cw20Contract(contract.clone()).balance(&ctx.deps.querier, ctx.env.contract.address.clone()) else {
+          continue;
+        };
+        if balance.is_zero() {
+          continue;
+        }
+        messages.push(WasmMsg::Execute {
+          contract_addr: contract.to_string(),
+          msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: balance,
+          })?,
+          funds: vec![],
+        }.into());
+      }
+      Ok(Evacuation::transfers(messages))
     }
-    EvacuateAsset::Cw721 { contract, token_ids } => {
+    EvacuateAsset::Cw721 { contract, token_ids, start_after, limit } => {
       let mut messages: Vec<CosmosMsg> = vec![];
+      let contract = ctx.deps.api.addr_validate(&contract)?;
+      let owner = ctx.env.contract.address.to_string();
+      let token_ids = match token_ids {
+        Some(token_ids) => {
+          let mut owned = vec![];
+          for token_id in token_ids {
+            if is_cw721_owner(&ctx.deps.querier, &contract, &token_id, &owner)? {
+              owned.push(token_id);
+            }
+          }
+          owned
+        }
+        None => query_cw721_tokens(&ctx.deps.querier, &contract, &owner, start_after, limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT))?,
+      };
       for token_id in token_ids {
         messages.push(WasmMsg::Execute {
-          contract_addr: contract.clone(),
+          contract_addr: contract.to_string(),
           msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
             recipient: recipient.to_string(),
-            token_id: token_id.clone(),
+            token_id,
           })?,
           funds: vec![],
         }.into());
       }
-      return Ok(messages)
+      Ok(Evacuation::transfers(messages))
+    }
+    EvacuateAsset::Cw1155 { contract, token_ids } => {
+      let mut messages: Vec<CosmosMsg> = vec![];
+      let contract = ctx.deps.api.addr_validate(&contract)?;
+      let owner = ctx.env.contract.address.to_string();
+      let res: BatchBalanceResponse = ctx.deps.querier.query_wasm_smart(&contract, &Cw1155QueryMsg::BatchBalance {
+        owner: owner.clone(),
+        token_ids: token_ids.clone(),
+      })?;
+      if res.balances.len() != token_ids.len() {
+        return Err(ContractError::Generic(format!(
+          "Expected {} cw1155 balances, got {}", token_ids.len(), res.balances.len(),
+        )));
+      }
+      let batch: Vec<(String, Uint128)> = token_ids.into_iter()
+        .zip(res.balances)
+        .filter(|(_, balance)| !balance.is_zero())
+        .collect();
+      if !batch.is_empty() {
+        messages.push(WasmMsg::Execute {
+          contract_addr: contract.to_string(),
+          msg: to_json_binary(&Cw1155ExecuteMsg::BatchSendFrom {
+            from: owner,
+            to: recipient.to_string(),
+            batch,
+            msg: None,
+          })?,
+          funds: vec![],
+        }.into());
+      }
+      Ok(Evacuation::transfers(messages))
     }
   }
 }
+
+/// Whether `owner` owns the given cw721 token. Fails if the contract cannot tell, e.g. for
+/// nonexistent tokens.
+fn is_cw721_owner(querier: &QuerierWrapper, contract: &Addr, token_id: &str, owner: &str) -> StdResult<bool> {
+  let res: OwnerOfResponse = querier.query_wasm_smart(contract, &Cw721QueryMsg::OwnerOf {
+    token_id: token_id.to_string(),
+    include_expired: None,
+  })?;
+  Ok(res.owner == owner)
+}
+
+/// A page of at most `limit` cw721 tokens of the given contract owned by `owner`.
+fn query_cw721_tokens(
+  querier: &QuerierWrapper,
+  contract: &Addr,
+  owner: &str,
+  start_after: Option<String>,
+  limit: u32,
+) -> StdResult<Vec<String>> {
+  let page: TokensResponse = querier.query_wasm_smart(contract, &Cw721QueryMsg::Tokens {
+    owner: owner.to_string(),
+    start_after,
+    limit: Some(limit),
+  })?;
+  // Do not rely on the contract honouring the limit
+  Ok(page.tokens.into_iter().take(limit as usize).collect())
+}
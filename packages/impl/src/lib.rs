@@ -1,6 +1,9 @@
+pub mod cw1155;
 pub mod error;
 pub mod execute;
+pub mod migrate;
 pub mod msg;
+pub mod state;
 pub mod tokenfactory;
 
 pub use error::ContractError;
\ No newline at end of file
@@ -0,0 +1,86 @@
+use cosmwasm_std::Storage;
+use cw2::get_contract_version;
+
+pub use semver::Version;
+
+use crate::error::ContractError;
+
+/// Parse a semver string, e.g. `CARGO_PKG_VERSION`
+pub fn parse_version(version: &str) -> Result<Version, ContractError> {
+  Version::parse(version).map_err(|err| ContractError::SemVer(err.to_string()))
+}
+
+/// Ensure the contract stored under cw2 may be migrated to the given name & version. Refuses
+/// migrating to a different contract & downgrading. Returns the previously stored version, which
+/// can then be used to decide which state migrations to run.
+pub fn ensure_migratable(
+  storage: &dyn Storage,
+  contract_name: &str,
+  contract_version: &str,
+) -> Result<Version, ContractError> {
+  let stored = get_contract_version(storage)?;
+  if stored.contract != contract_name {
+    return Err(ContractError::ContractMismatch {
+      expected: contract_name.to_string(),
+      actual: stored.contract,
+    });
+  }
+
+  let from = parse_version(&stored.version)?;
+  let to = parse_version(contract_version)?;
+  if from > to {
+    return Err(ContractError::Downgrade {
+      from: from.to_string(),
+      to: to.to_string(),
+    });
+  }
+  Ok(from)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cosmwasm_std::testing::MockStorage;
+  use cw2::set_contract_version;
+
+  #[test]
+  fn test_ensure_migratable() {
+    let mut storage = MockStorage::new();
+    set_contract_version(&mut storage, "soulp", "0.1.0").unwrap();
+
+    let from = ensure_migratable(&storage, "soulp", "0.2.0").unwrap();
+    assert_eq!(from, Version::new(0, 1, 0));
+
+    // Re-running a migration on the same version is permitted
+    let from = ensure_migratable(&storage, "soulp", "0.1.0").unwrap();
+    assert_eq!(from, Version::new(0, 1, 0));
+  }
+
+  #[test]
+  fn test_ensure_migratable_downgrade() {
+    let mut storage = MockStorage::new();
+    set_contract_version(&mut storage, "soulp", "0.2.0").unwrap();
+
+    match ensure_migratable(&storage, "soulp", "0.1.0").unwrap_err() {
+      ContractError::Downgrade { from, to } => {
+        assert_eq!(from, "0.2.0");
+        assert_eq!(to, "0.1.0");
+      }
+      _ => panic!("Expected Downgrade error"),
+    }
+  }
+
+  #[test]
+  fn test_ensure_migratable_contract_mismatch() {
+    let mut storage = MockStorage::new();
+    set_contract_version(&mut storage, "other", "0.1.0").unwrap();
+
+    match ensure_migratable(&storage, "soulp", "0.2.0").unwrap_err() {
+      ContractError::ContractMismatch { expected, actual } => {
+        assert_eq!(expected, "soulp");
+        assert_eq!(actual, "other");
+      }
+      _ => panic!("Expected ContractMismatch error"),
+    }
+  }
+}
@@ -1,15 +1,50 @@
 use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Coin, Uint128};
 
 #[cw_serde]
 pub enum EvacuateAsset {
-  /// Evacuate all native assets (except the pool token)
-  Native {},
+  /// Evacuate all native assets (except the pool token) in a single transfer. The contract's own
+  /// token is burned instead, if selected.
+  Native {
+    /// Only evacuate these denoms
+    denoms: Option<Vec<String>>,
+    /// Do not evacuate these denoms
+    exclude: Option<Vec<String>>,
+    /// Evacuate exactly these amounts rather than the full balances of their denoms, at most one
+    /// per denom
+    amounts: Option<Vec<Coin>>,
+    /// Only evacuate denoms after this one, in alphabetical order
+    start_after: Option<String>,
+    /// Evacuate at most this many denoms, capped at 100
+    limit: Option<u32>,
+  },
   /// Evacuate given cw20 asset
   Cw20 {
     contract: String,
+    /// Amount to evacuate. Defaults to the full balance.
+    amount: Option<Uint128>,
+  },
+  /// Evacuate the full balances of all tracked cw20 assets. Tokens whose balance cannot be
+  /// queried are skipped.
+  AllCw20 {
+    /// Only evacuate tracked tokens after this contract address
+    start_after: Option<String>,
+    /// Evacuate at most this many tracked tokens, capped at 100
+    limit: Option<u32>,
   },
-  /// Evacuate given cw721 NFTs
+  /// Evacuate given cw721 NFTs. Tokens owned by others are skipped, while tokens whose owner cannot
+  /// be queried fail the evacuation.
   Cw721 {
+    contract: String,
+    /// Tokens to evacuate. Defaults to a page of the tokens owned by this contract.
+    token_ids: Option<Vec<String>>,
+    /// Without `token_ids`, only evacuate owned tokens after this one
+    start_after: Option<String>,
+    /// Without `token_ids`, evacuate at most this many owned tokens, capped at 100
+    limit: Option<u32>,
+  },
+  /// Evacuate the full balances of given cw1155 tokens. Tokens without balance are skipped.
+  Cw1155 {
     contract: String,
     token_ids: Vec<String>,
   },
@@ -0,0 +1,22 @@
+use cosmwasm_std::{Addr, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Map};
+
+/// Cw20 contracts which sent tokens to this contract or were registered for evacuation
+pub const CW20_TOKENS: Map<&Addr, ()> = Map::new("cw20_tokens");
+
+const DEFAULT_LIMIT: u32 = 30;
+/// Most entries returned or evacuated per page
+pub const MAX_LIMIT: u32 = 100;
+
+/// Tracked cw20 contracts in order, optionally paginated.
+pub fn tracked_cw20s(
+  storage: &dyn Storage,
+  start_after: Option<&Addr>,
+  limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+  let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+  CW20_TOKENS
+    .keys(storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+    .take(limit)
+    .collect()
+}
@@ -1,5 +1,6 @@
+use cosmwasm_schema::cw_serde;
 use prost::Message;
-use cosmwasm_std::{Addr, CosmosMsg, Env, Uint128};
+use cosmwasm_std::{to_json_vec, Addr, ContractResult, CosmosMsg, Empty, Env, QuerierWrapper, QueryRequest, StdError, StdResult, SystemResult, Uint128};
 
 pub trait TFToken {
   /// Address of the owner of the token, usually the contract address
@@ -16,6 +17,33 @@ pub trait TFToken {
 
   /// Mint tokens to a recipient
   fn mint(&self, amount: Uint128, recipient: String) -> Vec<CosmosMsg>;
+
+  /// Burn tokens held by the owner
+  fn burn(&self, amount: Uint128) -> Vec<CosmosMsg>;
+
+  /// Set the bank metadata of the token, as displayed by wallets & explorers
+  fn set_metadata(&self, metadata: TokenMetadata) -> Vec<CosmosMsg>;
+
+  /// Transfer the admin of the token, i.e. the permission to mint & set metadata. An empty admin
+  /// renounces it, preventing any further minting.
+  fn change_admin(&self, new_admin: String) -> Vec<CosmosMsg>;
+
+  /// Query the current total supply of the token
+  fn supply(&self, querier: &QuerierWrapper) -> StdResult<Uint128>;
+}
+
+/// Human readable token metadata
+#[cw_serde]
+pub struct TokenMetadata {
+  /// Display name, e.g. "Astroport ATOM-NTRN SouLP"
+  pub name: String,
+  /// Ticker symbol, e.g. "ATOMNTRN"
+  pub symbol: String,
+  pub description: String,
+  /// Decimals of the display unit
+  pub exponent: u32,
+  /// URI of the token logo
+  pub logo_uri: Option<String>,
 }
 
 #[derive(Clone, PartialEq, Message)]
@@ -30,6 +58,89 @@ impl Coin {
   pub const TYPE_URL: &'static str = "/cosmos.bank.v1beta1.Coin";
 }
 
+#[derive(Clone, PartialEq, Message)]
+pub struct DenomUnit {
+  #[prost(string, tag = "1")]
+  pub denom: String,
+  #[prost(uint32, tag = "2")]
+  pub exponent: u32,
+  #[prost(string, repeated, tag = "3")]
+  pub aliases: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Metadata {
+  #[prost(string, tag = "1")]
+  pub description: String,
+  #[prost(message, repeated, tag = "2")]
+  pub denom_units: Vec<DenomUnit>,
+  #[prost(string, tag = "3")]
+  pub base: String,
+  #[prost(string, tag = "4")]
+  pub display: String,
+  #[prost(string, tag = "5")]
+  pub name: String,
+  #[prost(string, tag = "6")]
+  pub symbol: String,
+  #[prost(string, tag = "7")]
+  pub uri: String,
+  #[prost(string, tag = "8")]
+  pub uri_hash: String,
+}
+
+impl Metadata {
+  pub const TYPE_URL: &'static str = "/cosmos.bank.v1beta1.Metadata";
+
+  /// Bank metadata for the given base denom. The display unit is named after the symbol, unless
+  /// the token has no decimals.
+  pub fn new(base: String, metadata: TokenMetadata) -> Self {
+    let mut denom_units = vec![DenomUnit {
+      denom: base.clone(),
+      exponent: 0,
+      aliases: vec![],
+    }];
+    let display = if metadata.exponent > 0 {
+      denom_units.push(DenomUnit {
+        denom: metadata.symbol.clone(),
+        exponent: metadata.exponent,
+        aliases: vec![],
+      });
+      metadata.symbol.clone()
+    } else {
+      base.clone()
+    };
+
+    Metadata {
+      description: metadata.description,
+      denom_units,
+      base,
+      display,
+      name: metadata.name,
+      symbol: metadata.symbol,
+      uri: metadata.logo_uri.unwrap_or_default(),
+      uri_hash: String::new(),
+    }
+  }
+}
+
+/// Perform a stargate query & decode its protobuf response
+pub fn query_stargate<Res: Message + Default>(
+  querier: &QuerierWrapper,
+  path: &str,
+  request: impl Message,
+) -> StdResult<Res> {
+  let request: QueryRequest<Empty> = QueryRequest::Stargate {
+    path: path.to_string(),
+    data: request.encode_to_vec().into(),
+  };
+  let response = match querier.raw_query(&to_json_vec(&request)?) {
+    SystemResult::Err(err) => return Err(StdError::generic_err(format!("Querier system error: {}", err))),
+    SystemResult::Ok(ContractResult::Err(err)) => return Err(StdError::generic_err(format!("Querier contract error: {}", err))),
+    SystemResult::Ok(ContractResult::Ok(response)) => response,
+  };
+  Res::decode(response.as_slice()).map_err(|err| StdError::parse_err(std::any::type_name::<Res>(), err))
+}
+
 pub mod osmosis {
   use super::*;
 
@@ -45,11 +156,11 @@ pub mod osmosis {
     pub const TYPE_URL: &'static str = "/osmosis.tokenfactory.v1beta1.MsgCreateDenom";
   }
 
-  impl Into<CosmosMsg> for MsgCreateDenom {
-    fn into(self) -> CosmosMsg {
+  impl From<MsgCreateDenom> for CosmosMsg {
+    fn from(msg: MsgCreateDenom) -> CosmosMsg {
       CosmosMsg::Stargate {
-        type_url: Self::TYPE_URL.to_string(),
-        value: self.encode_to_vec().into(),
+        type_url: MsgCreateDenom::TYPE_URL.to_string(),
+        value: msg.encode_to_vec().into(),
       }
     }
   }
@@ -80,15 +191,114 @@ pub mod osmosis {
     }
   }
 
-  impl Into<CosmosMsg> for MsgMint {
-    fn into(self) -> CosmosMsg {
+  impl From<MsgMint> for CosmosMsg {
+    fn from(msg: MsgMint) -> CosmosMsg {
+      CosmosMsg::Stargate {
+        type_url: MsgMint::TYPE_URL.to_string(),
+        value: msg.encode_to_vec().into(),
+      }
+    }
+  }
+
+  #[derive(Clone, PartialEq, Message)]
+  pub struct MsgBurn {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(message, optional, tag = "2")]
+    pub amount: Option<Coin>,
+    #[prost(string, tag = "3")]
+    pub burn_from_address: String,
+  }
+
+  impl MsgBurn {
+    pub const TYPE_URL: &'static str = "/osmosis.tokenfactory.v1beta1.MsgBurn";
+  }
+
+  impl From<MsgBurn> for CosmosMsg {
+    fn from(msg: MsgBurn) -> CosmosMsg {
+      CosmosMsg::Stargate {
+        type_url: MsgBurn::TYPE_URL.to_string(),
+        value: msg.encode_to_vec().into(),
+      }
+    }
+  }
+
+  #[derive(Clone, PartialEq, Message)]
+  pub struct MsgChangeAdmin {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub denom: String,
+    #[prost(string, tag = "3")]
+    pub new_admin: String,
+  }
+
+  impl MsgChangeAdmin {
+    pub const TYPE_URL: &'static str = "/osmosis.tokenfactory.v1beta1.MsgChangeAdmin";
+  }
+
+  impl From<MsgChangeAdmin> for CosmosMsg {
+    fn from(msg: MsgChangeAdmin) -> CosmosMsg {
+      CosmosMsg::Stargate {
+        type_url: MsgChangeAdmin::TYPE_URL.to_string(),
+        value: msg.encode_to_vec().into(),
+      }
+    }
+  }
+
+  #[derive(Clone, PartialEq, Message)]
+  pub struct MsgSetDenomMetadata {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(message, optional, tag = "2")]
+    pub metadata: Option<Metadata>,
+  }
+
+  impl MsgSetDenomMetadata {
+    pub const TYPE_URL: &'static str = "/osmosis.tokenfactory.v1beta1.MsgSetDenomMetadata";
+  }
+
+  impl From<MsgSetDenomMetadata> for CosmosMsg {
+    fn from(msg: MsgSetDenomMetadata) -> CosmosMsg {
       CosmosMsg::Stargate {
-        type_url: Self::TYPE_URL.to_string(),
-        value: self.encode_to_vec().into(),
+        type_url: MsgSetDenomMetadata::TYPE_URL.to_string(),
+        value: msg.encode_to_vec().into(),
       }
     }
   }
 
+  #[derive(Clone, PartialEq, Message)]
+  pub struct QueryDenomAuthorityMetadataRequest {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+  }
+
+  impl QueryDenomAuthorityMetadataRequest {
+    pub const PATH: &'static str = "/osmosis.tokenfactory.v1beta1.Query/DenomAuthorityMetadata";
+  }
+
+  #[derive(Clone, PartialEq, Message)]
+  pub struct DenomAuthorityMetadata {
+    #[prost(string, tag = "1")]
+    pub admin: String,
+  }
+
+  #[derive(Clone, PartialEq, Message)]
+  pub struct QueryDenomAuthorityMetadataResponse {
+    #[prost(message, optional, tag = "1")]
+    pub authority_metadata: Option<DenomAuthorityMetadata>,
+  }
+
+  /// Query the admin of a tokenfactory denom. Empty if the denom has no admin.
+  pub fn query_denom_admin(querier: &QuerierWrapper, denom: &str) -> StdResult<String> {
+    let response: QueryDenomAuthorityMetadataResponse = query_stargate(
+      querier,
+      QueryDenomAuthorityMetadataRequest::PATH,
+      QueryDenomAuthorityMetadataRequest { denom: denom.to_string() },
+    )?;
+    Ok(response.authority_metadata.map(|metadata| metadata.admin).unwrap_or_default())
+  }
+
   pub struct TFToken {
     pub owner: Addr,
     pub subdenom: String,
@@ -127,5 +337,89 @@ pub mod osmosis {
         mint_to_address: recipient,
       }.into()]
     }
+
+    fn burn(&self, amount: Uint128) -> Vec<CosmosMsg> {
+      vec![MsgBurn {
+        sender: self.owner.to_string(),
+        amount: Some(Coin {
+          denom: self.denom(),
+          amount: amount.to_string(),
+        }),
+        burn_from_address: self.owner.to_string(),
+      }.into()]
+    }
+
+    fn set_metadata(&self, metadata: TokenMetadata) -> Vec<CosmosMsg> {
+      vec![MsgSetDenomMetadata {
+        sender: self.owner.to_string(),
+        metadata: Some(Metadata::new(self.denom(), metadata)),
+      }.into()]
+    }
+
+    fn change_admin(&self, new_admin: String) -> Vec<CosmosMsg> {
+      vec![MsgChangeAdmin {
+        sender: self.owner.to_string(),
+        denom: self.denom(),
+        new_admin,
+      }.into()]
+    }
+
+    fn supply(&self, querier: &QuerierWrapper) -> StdResult<Uint128> {
+      Ok(querier.query_supply(self.denom())?.amount)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use super::osmosis::{MsgBurn, MsgChangeAdmin};
+  use cosmwasm_std::testing::MockQuerier;
+  use cosmwasm_std::coins;
+
+  fn token() -> osmosis::TFToken {
+    osmosis::TFToken::new(Addr::unchecked("contract"), "SouLP".to_string())
+  }
+
+  fn decode<M: Message + Default>(msg: &CosmosMsg, expected_type_url: &str) -> M {
+    match msg {
+      CosmosMsg::Stargate { type_url, value } => {
+        assert_eq!(type_url, expected_type_url);
+        M::decode(value.as_slice()).unwrap()
+      }
+      _ => panic!("Expected Stargate message"),
+    }
+  }
+
+  #[test]
+  fn test_burn() {
+    let msgs = token().burn(Uint128::new(100));
+    assert_eq!(msgs.len(), 1);
+    let msg: MsgBurn = decode(&msgs[0], MsgBurn::TYPE_URL);
+    assert_eq!(msg.sender, "contract");
+    assert_eq!(msg.burn_from_address, "contract");
+    let amount = msg.amount.unwrap();
+    assert_eq!(amount.denom, "factory/contract/SouLP");
+    assert_eq!(amount.amount, "100");
+  }
+
+  #[test]
+  fn test_change_admin() {
+    let msgs = token().change_admin("new_admin".to_string());
+    assert_eq!(msgs.len(), 1);
+    let msg: MsgChangeAdmin = decode(&msgs[0], MsgChangeAdmin::TYPE_URL);
+    assert_eq!(msg.sender, "contract");
+    assert_eq!(msg.denom, "factory/contract/SouLP");
+    assert_eq!(msg.new_admin, "new_admin");
+  }
+
+  #[test]
+  fn test_supply() {
+    let querier: MockQuerier = MockQuerier::new(&[
+      ("alice", &coins(100, "factory/contract/SouLP")),
+      ("bob", &coins(50, "factory/contract/SouLP")),
+    ]);
+    let querier = QuerierWrapper::<Empty>::new(&querier);
+    assert_eq!(token().supply(&querier).unwrap(), Uint128::new(150));
   }
 }
@@ -1,15 +1,28 @@
-use cosmwasm_std::{to_json_binary, Querier, QuerierResult, QueryRequest, WasmQuery};
+use cosmwasm_std::{to_json_binary, Binary, Coin, ContractInfoResponse, Querier, QuerierResult, QueryRequest, WasmQuery};
 use cw20::{BalanceResponse, Cw20QueryMsg};
 
-/// Custom querier to handle CW20 queries
+/// Custom querier to handle CW20, contract info & stargate queries. Bank queries are forwarded to
+/// the standard `MockQuerier`.
 pub struct MockQuerier {
+  base: cosmwasm_std::testing::MockQuerier,
   cw20_balances: std::collections::HashMap<String, u128>,
+  contract_infos: std::collections::HashMap<String, ContractInfoResponse>,
+  stargate_responses: std::collections::HashMap<String, Binary>,
+}
+
+impl Default for MockQuerier {
+  fn default() -> Self {
+    Self::new()
+  }
 }
 
 impl MockQuerier {
   pub fn new() -> Self {
     Self {
+      base: cosmwasm_std::testing::MockQuerier::new(&[]),
       cw20_balances: std::collections::HashMap::new(),
+      contract_infos: std::collections::HashMap::new(),
+      stargate_responses: std::collections::HashMap::new(),
     }
   }
 
@@ -17,6 +30,26 @@ impl MockQuerier {
     self.cw20_balances.insert(contract.to_string(), balance);
     self
   }
+
+  pub fn with_balance(mut self, address: &str, balance: Vec<Coin>) -> Self {
+    self.base.update_balance(address, balance);
+    self
+  }
+
+  pub fn with_contract_admin(mut self, contract: &str, admin: Option<&str>) -> Self {
+    let mut info = ContractInfoResponse::default();
+    info.code_id = 1;
+    info.creator = "creator".to_string();
+    info.admin = admin.map(|admin| admin.to_string());
+    self.contract_infos.insert(contract.to_string(), info);
+    self
+  }
+
+  /// Respond to the stargate query at `path` with the given (protobuf encoded) response
+  pub fn with_stargate_response(mut self, path: &str, response: impl Into<Binary>) -> Self {
+    self.stargate_responses.insert(path.to_string(), response.into());
+    self
+  }
 }
 
 impl Querier for MockQuerier {
@@ -32,6 +65,9 @@ impl Querier for MockQuerier {
     };
 
     match request {
+      QueryRequest::Bank(_) => {
+        return self.base.handle_query(&request);
+      }
       QueryRequest::Wasm(WasmQuery::Smart { msg, .. }) => {
         // Handle CW20 balance queries
         if let Ok(balance_query) = cosmwasm_std::from_json::<Cw20QueryMsg>(&msg) {
@@ -53,6 +89,20 @@ impl Querier for MockQuerier {
           }
         }
       }
+      QueryRequest::Wasm(WasmQuery::ContractInfo { contract_addr }) => {
+        if let Some(info) = self.contract_infos.get(&contract_addr) {
+          return cosmwasm_std::SystemResult::Ok(
+            cosmwasm_std::ContractResult::Ok(to_json_binary(info).unwrap())
+          );
+        }
+      }
+      QueryRequest::Stargate { path, .. } => {
+        if let Some(response) = self.stargate_responses.get(&path) {
+          return cosmwasm_std::SystemResult::Ok(
+            cosmwasm_std::ContractResult::Ok(response.clone())
+          );
+        }
+      }
       _ => {
         println!("Unknown Request: {:?}", request);
       }